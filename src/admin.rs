@@ -0,0 +1,174 @@
+//! Endpoints under `/admin`, for `Authorization: Bearer <ADMIN_SECRET>`.
+//! Without that secret set, they answer 404.
+
+use serde::Serialize;
+use std::future::Future;
+use worker::kv::KvStore;
+use worker::{console_error, console_log, Context, Request, Response, Result, RouteContext};
+
+use crate::users::{self, BotUser};
+use crate::{commands, discord};
+
+#[derive(Debug, Serialize)]
+struct DryRun {
+    batch_size: usize,
+    next: Vec<String>,
+    queued: usize,
+    drawn: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Counts {
+    users: usize,
+    active: usize,
+    paused: usize,
+    pending_adds: usize,
+    pending_deletes: usize,
+    commands: usize,
+}
+
+/// Runs `handler` for authorized requests only.
+pub async fn guard<F, T>(req: Request, ctx: RouteContext<Context>, handler: F) -> Result<Response>
+where
+    F: FnOnce(Request, RouteContext<Context>) -> T,
+    T: Future<Output = Result<Response>>,
+{
+    match authorized(&req, &ctx) {
+        Some(true) => handler(req, ctx).await,
+        Some(false) => Response::error("Unauthorized", 401),
+        None => Response::error("Not Found", 404),
+    }
+}
+
+/// `None` when there is no `ADMIN_SECRET`, and the endpoints are disabled.
+fn authorized(req: &Request, ctx: &RouteContext<Context>) -> Option<bool> {
+    let secret = ctx.secret("ADMIN_SECRET").ok()?.to_string();
+    if secret.is_empty() {
+        return None;
+    }
+    let header = req
+        .headers()
+        .get("Authorization")
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    Some(
+        header
+            .strip_prefix("Bearer ")
+            .is_some_and(|token| same_secret(token.as_bytes(), secret.as_bytes())),
+    )
+}
+
+/// Compares in constant time, so response times don't leak the secret.
+fn same_secret(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Syncs the commands with Discord, whether or not they changed.
+pub async fn sync_commands(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let mut client = discord::Client::from_env(&ctx.env)?;
+    if commands::update(&ctx.env, &mut client, &ctx.kv("thankful")?, true).await {
+        Response::ok("Commands synced.")
+    } else {
+        Response::error("Couldn't sync commands, see the logs for details.", 502)
+    }
+}
+
+/// Shows the next batch of the queue and a sample draw, without reminding or
+/// queueing anyone.
+pub async fn dry_run(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let users = match registered(&ctx).await {
+        Ok(users) => users,
+        Err(response) => return response,
+    };
+    let mut queue = match users::queued(&ctx.kv("thankful")?).await {
+        Ok(queue) => queue,
+        Err(err) => return Response::error(err.to_string(), 500),
+    };
+    let batch_size = users::batch_size(&ctx.env);
+    let queued = queue.len();
+    queue.truncate(batch_size);
+    Response::from_json(&DryRun {
+        batch_size,
+        next: queue,
+        queued,
+        drawn: users::draw(&users),
+    })
+}
+
+/// Reminds the user in the `uid` parameter right away, paused or not.
+pub async fn prompt(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let Some(uid) = ctx.param("uid").cloned() else {
+        return Response::error("Missing user ID", 400);
+    };
+    let users = match registered(&ctx).await {
+        Ok(users) => users,
+        Err(response) => return response,
+    };
+    let Some(mut user) = users.into_iter().find(|user| user.uid == uid) else {
+        return Response::error("User not registered", 404);
+    };
+
+    console_log!("Prompting {} on request", uid);
+    let mut client = discord::Client::from_env(&ctx.env)?;
+    let sent = user.prompt(&ctx.kv("thankful")?, &mut client).await;
+    if let Err(err) = users::save_user(&user, &users_kv(&ctx)?).await {
+        console_error!("Couldn't save user {}: {}", uid, err);
+    }
+    match sent {
+        Ok(()) => Response::ok("Prompted."),
+        Err(err) => Response::error(format!("Couldn't prompt: {}", err), 502),
+    }
+}
+
+pub async fn counts(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let users = match registered(&ctx).await {
+        Ok(users) => users,
+        Err(response) => return response,
+    };
+    let paused = users.iter().filter(|user| user.paused.is_some()).count();
+
+    let (mut pending_adds, mut pending_deletes) = (0, 0);
+    let kv = users_kv(&ctx)?;
+    let mut cursor = None;
+    loop {
+        let mut list = kv.list();
+        if let Some(cursor) = cursor {
+            list = list.cursor(cursor);
+        }
+        let page = list.execute().await.map_err(worker::Error::from)?;
+        for key in page.keys {
+            match key.name.split_once(' ') {
+                Some(("ADD", _)) => pending_adds += 1,
+                Some(("DELETE", _)) => pending_deletes += 1,
+                _ => {}
+            }
+        }
+        if page.list_complete {
+            break;
+        }
+        cursor = page.cursor;
+    }
+
+    Response::from_json(&Counts {
+        users: users.len(),
+        active: users.len() - paused,
+        paused,
+        pending_adds,
+        pending_deletes,
+        commands: commands::registry().len(),
+    })
+}
+
+fn users_kv(ctx: &RouteContext<Context>) -> Result<KvStore> {
+    ctx.kv("grateful_users")
+}
+
+async fn registered(
+    ctx: &RouteContext<Context>,
+) -> std::result::Result<Vec<BotUser>, Result<Response>> {
+    let kv = users_kv(ctx).map_err(Err)?;
+    users::registered(&kv)
+        .await
+        .map_err(|err| Response::error(err.to_string(), 500))
+}
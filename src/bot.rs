@@ -1,18 +1,26 @@
+use crate::commands::{self, CommandContext, CommandResponse};
 use crate::discord;
-use crate::error;
-use crate::interaction::data_types::{InteractionVariants, PingInteraction};
-use crate::verification::verify_signature;
-use serde_json::from_str;
+use crate::error::{self, Respond};
+use crate::interaction::data_types::{
+    AutocompleteResponse, CommandName, InteractionVariants, PingInteraction, SimpleMessageResponse,
+};
+use crate::interaction::processed::{self, Record};
+use crate::users;
+use crate::verification::{public_keys, verify_signature, verify_timestamp};
+use std::future::Future;
 use worker::Response as Res;
-use worker::{console_log, Request, RouteContext};
+use worker::{console_error, console_log, Context, Date, Request, RouteContext};
+
+/// Requests signed more than five minutes away from now are rejected.
+const DEFAULT_MAX_SKEW: u64 = 5 * 60;
 
 pub struct App {
     req: Request,
-    ctx: RouteContext<()>,
+    ctx: RouteContext<Context>,
 }
 
 impl App {
-    pub fn new(req: Request, ctx: RouteContext<()>) -> Self {
+    pub fn new(req: Request, ctx: RouteContext<Context>) -> Self {
         Self { req, ctx }
     }
 
@@ -30,8 +38,17 @@ impl App {
         }
     }
 
+    /// Seconds a request's timestamp may be off by, from `SIGNATURE_MAX_SKEW`.
+    fn max_skew(&self) -> u64 {
+        self.var("SIGNATURE_MAX_SKEW")
+            .ok()
+            .and_then(|skew| skew.parse().ok())
+            .unwrap_or(DEFAULT_MAX_SKEW)
+    }
+
     async fn validate_sig(&mut self) -> Result<String, error::General> {
-        let pubkey = self.var("DISCORD_PUBLIC_KEY")?;
+        let public_keys = public_keys(&self.var("DISCORD_PUBLIC_KEY")?)
+            .map_err(error::General::VerificationFailed)?;
         let signature = self.header("x-signature-ed25519")?;
         let timestamp = self.header("x-signature-timestamp")?;
 
@@ -40,11 +57,59 @@ impl App {
             .text()
             .await
             .map_err(|_| error::General::InvalidPayload(String::new()))?;
-        verify_signature(&pubkey, &signature, &timestamp, &body)
+        verify_signature(&public_keys, &signature, &timestamp, &body)
+            .map_err(error::General::VerificationFailed)?;
+        verify_timestamp(&timestamp, Date::now().as_millis() / 1000, self.max_skew())
             .map_err(error::General::VerificationFailed)?;
         Ok(body)
     }
 
+    /// Acknowledges right away and edits the reply once `work` is done.
+    /// Lookup and mark aren't atomic, so a quick repeat may still run twice.
+    async fn defer<F>(
+        &self,
+        interaction_id: String,
+        token: String,
+        ephemeral: bool,
+        work: F,
+    ) -> Result<Res, error::Http>
+    where
+        F: Future<Output = SimpleMessageResponse> + 'static,
+    {
+        let kv = self.ctx.env.kv("thankful")?;
+        match processed::lookup(&kv, &interaction_id).await {
+            Ok(Some(Record::Done(response))) => {
+                console_log!("Interaction {} already handled", interaction_id);
+                return Ok(Res::from_json(&response)?);
+            }
+            Ok(Some(Record::Pending)) => {
+                console_log!("Interaction {} still being handled", interaction_id);
+                return Ok(Res::from_json(&SimpleMessageResponse::deferred(ephemeral))?);
+            }
+            Ok(None) => {}
+            Err(err) => console_error!("Couldn't look up interaction: {}", err),
+        }
+
+        let application_id = self.var("DISCORD_APPLICATION_ID")?;
+        let mut client = discord::Client::from_env(&self.ctx.env)?;
+        self.ctx.data.wait_until(async move {
+            if let Err(err) = processed::start(&kv, &interaction_id).await {
+                console_error!("Couldn't record interaction: {}", err);
+            }
+            let response = work.await;
+            if let Err(err) = client
+                .edit_original_response(&application_id, &token, &response.data)
+                .await
+            {
+                console_error!("Couldn't edit original response: {}", err);
+            }
+            if let Err(err) = processed::finish(&kv, &interaction_id, response).await {
+                console_error!("Couldn't record interaction response: {}", err);
+            }
+        });
+        Ok(Res::from_json(&SimpleMessageResponse::deferred(ephemeral))?)
+    }
+
     pub async fn handle_request(&mut self) -> Result<Res, error::Http> {
         let body = self.validate_sig().await?;
         let thankful_kv = self
@@ -52,7 +117,7 @@ impl App {
             .env
             .kv("thankful")
             .expect("Worker should have access to thankful binding");
-        let client = discord::Client::new(&discord::token(&self.ctx.env).unwrap());
+        let client = discord::Client::from_env(&self.ctx.env)?;
         let users_kv = self
             .ctx
             .env
@@ -61,14 +126,70 @@ impl App {
 
         console_log!("Request body : {}", body);
 
-        match from_str::<InteractionVariants>(&body).map_err(error::General::from)? {
-            InteractionVariants::Ping(_) => Ok(Res::from_json(&PingInteraction::handle())?),
-            InteractionVariants::Command(i) => Ok(Res::from_json(
-                &i.handle(client, users_kv, thankful_kv).await,
-            )?),
-            InteractionVariants::Button(i) => Ok(Res::from_json(&i.handle_grateful())?),
+        let interaction = InteractionVariants::parse(&body).map_err(error::General::from)?;
+        // `/start` unpauses the user, and tells them so itself.
+        let starting = matches!(
+            &interaction,
+            InteractionVariants::Command(i) if i.data.name == CommandName::Start
+        );
+        if let Some((uid, locale)) = interaction.author().filter(|_| !starting) {
+            let kv = thankful_kv.clone();
+            let mut client = discord::Client::from_env(&self.ctx.env)?;
+            self.ctx.data.wait_until(async move {
+                users::notify_paused(&kv, &uid, locale, &mut client).await;
+            });
+        }
+
+        match interaction {
+            InteractionVariants::Ping => Ok(Res::from_json(&PingInteraction::handle())?),
+            InteractionVariants::Autocomplete => {
+                Ok(Res::from_json(&AutocompleteResponse::empty())?)
+            }
+            InteractionVariants::Unsupported(locale) => {
+                Ok(Res::from_json(&SimpleMessageResponse::unsupported(locale))?)
+            }
+            InteractionVariants::Command(i) => {
+                let ctx = CommandContext {
+                    client,
+                    users_kv,
+                    thankful_kv,
+                };
+                let Some(command) = commands::find(i.data.name) else {
+                    console_error!("No handler registered for command {:?}!", i.data.name);
+                    return Ok(Res::from_json(&SimpleMessageResponse::error(i.locale()))?);
+                };
+                if !command.deferred() {
+                    return Ok(Res::from_json(&command.handle(&i, ctx).await)?);
+                }
+                self.defer(i.id.clone(), i.token.clone(), true, async move {
+                    match command.handle(&i, ctx).await {
+                        CommandResponse::Message(response) => response,
+                        CommandResponse::Modal(_) => {
+                            console_error!(
+                                "Deferred command {:?} replied with a modal!",
+                                i.data.name
+                            );
+                            SimpleMessageResponse::error(i.locale())
+                        }
+                    }
+                })
+                .await
+            }
+            InteractionVariants::Button(i) => match i.handle_grateful() {
+                Ok(modal) => Ok(Res::from_json(&modal)?),
+                Err(err) => Ok(Res::from_json(&err.respond(i.locale()))?),
+            },
             InteractionVariants::Modal(mut i) => {
-                Ok(Res::from_json(&i.handle(thankful_kv, client).await)?)
+                self.defer(i.id.clone(), i.token.clone(), false, async move {
+                    i.handle(thankful_kv, client).await
+                })
+                .await
+            }
+            InteractionVariants::GratitudeModal(i) => {
+                self.defer(i.id.clone(), i.token.clone(), true, async move {
+                    i.handle(thankful_kv, client).await
+                })
+                .await
             }
         }
     }
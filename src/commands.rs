@@ -1,188 +1,267 @@
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use worker::{console_debug, console_error, console_log, Env};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use worker::kv::KvStore;
+use worker::{console_error, console_log, Env};
 
-use crate::discord;
-use crate::interaction::{CommandName, CommandType, OptionType};
+use crate::discord::{self, DiscordError};
+use crate::interaction::{
+    ChannelType, CommandInteraction, CommandName, CommandType, DoubleTextInputModalResponse,
+    OptionChoice, OptionType, SimpleMessageResponse,
+};
+use crate::status;
 
-pub async fn update(env: &Env, client: &mut discord::Client) {
-    let application_id = env.var("DISCORD_APPLICATION_ID").unwrap().to_string();
+mod entry;
+mod help;
+mod send_gratitude;
+mod start;
+mod stop;
 
-    let mut registered = ApplicationCommand::registered(&application_id, client).await;
-    let mut available = ApplicationCommand::globals(&application_id);
-    let available_clone = available.clone();
+/// Every command the bot offers. Both command registration and interaction
+/// dispatch are driven from this list.
+pub fn registry() -> Vec<Box<dyn Command>> {
+    vec![
+        Box::new(help::Help),
+        Box::new(start::Start),
+        Box::new(stop::Stop),
+        Box::new(entry::Entry),
+        Box::new(send_gratitude::SendGratitude),
+    ]
+}
+
+#[async_trait(?Send)]
+pub trait Command {
+    fn name(&self) -> CommandName;
+    fn definition(&self, application_id: &str) -> ApplicationCommand;
+    /// Commands doing several KV or Discord round trips should be deferred,
+    /// and then reply through the interaction webhook instead.
+    fn deferred(&self) -> bool {
+        false
+    }
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> CommandResponse;
+}
 
-    available.retain(|c| !registered.has(c));
-    register(&available, client).await;
+pub struct CommandContext {
+    pub client: discord::Client,
+    pub users_kv: KvStore,
+    pub thankful_kv: KvStore,
+}
 
-    registered.retain(|c| !available_clone.has(c));
-    delete(&registered, client).await;
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CommandResponse {
+    Message(SimpleMessageResponse),
+    Modal(DoubleTextInputModalResponse),
 }
 
-pub async fn delete(commands: &Vec<ApplicationCommand>, client: &mut discord::Client) {
-    for command in commands {
-        command.delete(client).await;
+impl From<SimpleMessageResponse> for CommandResponse {
+    fn from(response: SimpleMessageResponse) -> Self {
+        Self::Message(response)
     }
 }
 
-pub async fn register(commands: &Vec<ApplicationCommand>, client: &mut discord::Client) {
-    for command in commands {
-        command.register(client).await;
+impl From<DoubleTextInputModalResponse> for CommandResponse {
+    fn from(response: DoubleTextInputModalResponse) -> Self {
+        Self::Modal(response)
     }
 }
 
-trait HasCommand {
-    fn has(&self, other: &ApplicationCommand) -> bool;
+pub fn find(name: CommandName) -> Option<Box<dyn Command>> {
+    registry()
+        .into_iter()
+        .find(|command| command.name() == name)
 }
 
-impl HasCommand for Vec<ApplicationCommand> {
-    fn has(&self, other: &ApplicationCommand) -> bool {
-        for command in self {
-            if command.name == other.name {
-                return true;
+/// Syncs the commands with Discord if they changed, or if `force` is set.
+/// Tells whether every sync succeeded.
+pub async fn update(env: &Env, client: &mut discord::Client, kv: &KvStore, force: bool) -> bool {
+    let application_id = match env.var("DISCORD_APPLICATION_ID") {
+        Ok(application_id) => application_id.to_string(),
+        Err(err) => {
+            console_error!("Couldn't get application ID: {}", err);
+            return false;
+        }
+    };
+
+    let synced = match test_guilds(env) {
+        Some(guilds) => {
+            // Commands registered globally before would show up twice in
+            // the test guilds, so the global set is cleared.
+            let mut synced = sync(&application_id, None, Vec::new(), client, kv, force).await;
+            for guild_id in guilds {
+                console_log!("Updating commands for test guild {}", guild_id);
+                let available = ApplicationCommand::for_guild(&application_id, &guild_id);
+                synced &= sync(
+                    &application_id,
+                    Some(&guild_id),
+                    available,
+                    client,
+                    kv,
+                    force,
+                )
+                .await;
             }
+            synced
         }
-        false
+        None => {
+            let available = ApplicationCommand::globals(&application_id);
+            sync(&application_id, None, available, client, kv, force).await
+        }
+    };
+    if synced {
+        status::record(kv, status::Job::CommandSync).await;
     }
+    synced
 }
 
-#[allow(dead_code)]
-impl ApplicationCommand {
-    pub fn globals(application_id: &str) -> Vec<Self> {
-        vec![
-            Self {
-                name: CommandName::Help,
-                application_id: application_id.to_string(),
-                description: "Get some information about the bot!".into(),
-                dm_permission: Some(true),
-                ..Default::default()
-            },
-            Self {
-                name: CommandName::Start,
-                application_id: application_id.to_string(),
-                description: "Start receiving reminders from the bot!".into(),
-                dm_permission: Some(true),
-                ..Default::default()
-            },
-            Self {
-                name: CommandName::Stop,
-                application_id: application_id.to_string(),
-                description: "Stop receiving reminders from the bot!".into(),
-                dm_permission: Some(true),
-                ..Default::default()
-            },
-            Self {
-                name: CommandName::Entry,
-                description: "Add an entry to your gratitude journal!".into(),
-                options: Some(vec![ApplicationCommandOption {
-                    r#type: OptionType::String,
-                    name: "entry".into(),
-                    description: "Something, anything, you are feeling grateful for!".into(),
-                    required: Some(true),
-                    min_length: Some(5),
-                    max_length: Some(1000),
-                }]),
-                application_id: application_id.to_string(),
-                dm_permission: Some(true),
-                ..Default::default()
-            },
-        ]
-    }
-
-    pub async fn registered(application_id: &str, client: &mut discord::Client) -> Vec<Self> {
-        let response = match client
-            .get(&format!("applications/{}/commands", application_id))
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(err) => {
-                console_error!("Couldn't find commands: {}", err);
-                panic!();
-            }
-        };
-        let commands = match response.error_for_status() {
-            Ok(response) => {
-                console_debug!("Response: {:#?}", response);
-                response.json::<Vec<ApplicationCommand>>().await
-            }
-            Err(err) => {
-                console_error!("Did not get expected response: {}", err);
-                panic!();
-            }
-        };
-        match commands {
-            Ok(commands) => commands,
-            Err(err) => {
-                console_error!("Couldn't parse commands response: {}", err);
-                panic!();
-            }
-        }
+/// Guilds listed in `DISCORD_TEST_GUILDS` (comma-separated) receive the
+/// commands directly, skipping the propagation delay of global commands.
+pub fn test_guilds(env: &Env) -> Option<Vec<String>> {
+    let guilds: Vec<String> = env
+        .var("DISCORD_TEST_GUILDS")
+        .ok()?
+        .to_string()
+        .split(',')
+        .map(str::trim)
+        .filter(|guild_id| !guild_id.is_empty())
+        .map(str::to_string)
+        .collect();
+    if guilds.is_empty() {
+        None
+    } else {
+        Some(guilds)
     }
+}
 
-    pub fn by_name(name: CommandName) -> Self {
-        Self {
-            name,
-            ..Default::default()
+/// Makes `available` the commands of the guild, or the global ones, unless
+/// their hash in `kv` shows they're unchanged. Tells whether that worked.
+async fn sync(
+    application_id: &str,
+    guild_id: Option<&str>,
+    available: Vec<ApplicationCommand>,
+    client: &mut discord::Client,
+    kv: &KvStore,
+    force: bool,
+) -> bool {
+    let key = hash_key(application_id, guild_id);
+    let hash = definitions_hash(&available);
+    match kv.get(&key).text().await {
+        Ok(Some(synced)) if synced == hash && !force => {
+            console_log!("Commands unchanged since the last sync!");
+            return true;
         }
+        Ok(_) => {}
+        Err(err) => console_error!("Couldn't get hash of synced commands: {}", err),
     }
 
-    pub async fn get_id(&self, client: &mut discord::Client) -> Option<String> {
-        let commands = ApplicationCommand::registered(&self.application_id, client).await;
-        match commands.iter().find(|command| command.name == self.name) {
-            Some(command) => command.id.clone(),
-            None => None,
+    let registered = match ApplicationCommand::registered(application_id, guild_id, client).await {
+        Ok(registered) => registered,
+        Err(err) => {
+            console_error!("Couldn't find commands: {}", err);
+            return false;
         }
+    };
+    if available.iter().all(|c| registered.has(c)) && registered.iter().all(|c| available.has(c)) {
+        console_log!("Commands up to date!");
+    } else if let Err(err) = overwrite(application_id, guild_id, &available, client).await {
+        console_error!("Couldn't overwrite commands: {}", err);
+        return false;
     }
 
-    pub async fn register(&self, client: &mut discord::Client) -> Self {
-        let response = match client
-            .post(&format!("applications/{}/commands", self.application_id))
-            .json(&CommandRegister::from(self))
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(err) => {
-                console_error!("Couldn't register command: {}", err);
-                panic!()
-            }
-        };
-        let command = match response.error_for_status() {
-            Ok(response) => {
-                console_debug!("Response: {:#?}", response);
-                response.json::<ApplicationCommand>().await
-            }
-            Err(err) => {
-                console_error!("Did not get expected response: {}", err);
-                panic!();
-            }
-        };
-        match command {
-            Ok(command) => command,
-            Err(err) => {
-                console_error!("Couldn't parse command response: {}", err);
-                panic!();
-            }
+    let stored = match kv.put(&key, hash) {
+        Ok(task) => task.execute().await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = stored {
+        console_error!("Couldn't store hash of synced commands: {}", err);
+    }
+    true
+}
+
+fn hash_key(application_id: &str, guild_id: Option<&str>) -> String {
+    format!(
+        "COMMANDS {}",
+        ApplicationCommand::path(application_id, guild_id)
+    )
+}
+
+/// Going through `Value` sorts the localization maps, so the same
+/// definitions always hash the same.
+fn definitions_hash(commands: &[ApplicationCommand]) -> String {
+    let payload: Vec<CommandRegister> = commands.iter().map(CommandRegister::from).collect();
+    let json = serde_json::to_value(payload)
+        .expect("Command definitions should serialize")
+        .to_string();
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+pub async fn overwrite(
+    application_id: &str,
+    guild_id: Option<&str>,
+    commands: &[ApplicationCommand],
+    client: &mut discord::Client,
+) -> Result<(), DiscordError> {
+    let payload: Vec<CommandRegister> = commands.iter().map(CommandRegister::from).collect();
+    let commands = client
+        .bulk_overwrite_commands(application_id, guild_id, &payload)
+        .await?;
+    console_log!("Commands overwritten: {:#?}", commands);
+    Ok(())
+}
+
+trait HasCommand {
+    fn has(&self, other: &ApplicationCommand) -> bool;
+}
+
+impl HasCommand for Vec<ApplicationCommand> {
+    fn has(&self, other: &ApplicationCommand) -> bool {
+        let other = CommandRegister::from(other);
+        self.iter()
+            .any(|command| CommandRegister::from(command).same_as(&other))
+    }
+}
+
+impl ApplicationCommand {
+    pub fn globals(application_id: &str) -> Vec<Self> {
+        registry()
+            .iter()
+            .map(|command| command.definition(application_id))
+            .collect()
+    }
+
+    pub fn for_guild(application_id: &str, guild_id: &str) -> Vec<Self> {
+        let mut commands = Self::globals(application_id);
+        for command in commands.iter_mut() {
+            command.guild_id = Some(guild_id.to_string());
         }
+        commands
     }
 
-    pub async fn delete(&self, client: &mut discord::Client) {
-        if let Some(ref id) = self.get_id(client).await {
-            match client
-                .delete(&format!(
-                    "applications/{}/commands/{}",
-                    self.application_id, id
-                ))
-                .send()
-                .await
-            {
-                Ok(response) => console_log!("Command {:?} deleted: {:#?}", self.name, response),
-                Err(err) => console_error!("Command {:?} not deleted: {}", self.name, err),
-            }
-        } else {
-            console_log!("Command not found!");
+    pub fn path(application_id: &str, guild_id: Option<&str>) -> String {
+        match guild_id {
+            Some(guild_id) => format!(
+                "applications/{}/guilds/{}/commands",
+                application_id, guild_id
+            ),
+            None => format!("applications/{}/commands", application_id),
         }
     }
+
+    pub async fn registered(
+        application_id: &str,
+        guild_id: Option<&str>,
+        client: &mut discord::Client,
+    ) -> Result<Vec<Self>, DiscordError> {
+        client.list_commands(application_id, guild_id).await
+    }
 }
 
 impl Default for ApplicationCommand {
@@ -193,7 +272,9 @@ impl Default for ApplicationCommand {
             application_id: String::new(),
             guild_id: None,
             name: CommandName::Start,
+            name_localizations: None,
             description: String::new(),
+            description_localizations: None,
             options: None,
             default_member_permissions: None,
             dm_permission: Some(true),
@@ -209,38 +290,89 @@ pub struct ApplicationCommand {
     pub application_id: String,
     pub guild_id: Option<String>,
     pub name: CommandName,
+    pub name_localizations: Option<HashMap<String, String>>,
     pub description: String,
+    pub description_localizations: Option<HashMap<String, String>>,
     pub options: Option<Vec<ApplicationCommandOption>>,
     pub default_member_permissions: Option<String>,
     pub dm_permission: Option<bool>,
     pub version: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct ApplicationCommandOption {
     pub r#type: OptionType,
     pub name: String,
+    pub name_localizations: Option<HashMap<String, String>>,
     pub description: String,
+    pub description_localizations: Option<HashMap<String, String>>,
     pub required: Option<bool>,
+    pub choices: Option<Vec<OptionChoice>>,
+    pub options: Option<Vec<ApplicationCommandOption>>,
+    pub channel_types: Option<Vec<ChannelType>>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
     pub min_length: Option<u32>,
     pub max_length: Option<u32>,
+    pub autocomplete: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct CommandRegister {
     pub name: CommandName,
+    pub name_localizations: Option<HashMap<String, String>>,
     pub description: String,
+    pub description_localizations: Option<HashMap<String, String>>,
     pub options: Option<Vec<ApplicationCommandOption>>,
     pub default_member_permissions: Option<String>,
     pub dm_permission: Option<bool>,
     pub r#type: Option<CommandType>,
 }
 
+impl CommandRegister {
+    /// Compares two definitions the way Discord sees them, treating fields
+    /// Discord fills in with defaults as if they had been set explicitly.
+    pub fn same_as(&self, other: &Self) -> bool {
+        self.clone().normalized() == other.clone().normalized()
+    }
+
+    fn normalized(mut self) -> Self {
+        self.r#type.get_or_insert(CommandType::ChatInput);
+        self.dm_permission.get_or_insert(true);
+        self.options = ApplicationCommandOption::normalized(self.options);
+        self
+    }
+}
+
+impl ApplicationCommandOption {
+    fn normalized(options: Option<Vec<Self>>) -> Option<Vec<Self>> {
+        let options: Vec<Self> = options?
+            .into_iter()
+            .map(|mut option| {
+                option.required.get_or_insert(false);
+                option.autocomplete.get_or_insert(false);
+                option.options = Self::normalized(option.options);
+                if option.choices.as_ref().is_some_and(Vec::is_empty) {
+                    option.choices = None;
+                }
+                option
+            })
+            .collect();
+        if options.is_empty() {
+            None
+        } else {
+            Some(options)
+        }
+    }
+}
+
 impl From<&ApplicationCommand> for CommandRegister {
     fn from(command: &ApplicationCommand) -> Self {
         Self {
             name: command.name,
+            name_localizations: command.name_localizations.clone(),
             description: command.description.clone(),
+            description_localizations: command.description_localizations.clone(),
             options: command.options.clone(),
             default_member_permissions: command.default_member_permissions.clone(),
             dm_permission: command.dm_permission,
@@ -248,3 +380,77 @@ impl From<&ApplicationCommand> for CommandRegister {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(application_id: &str) -> Vec<CommandRegister> {
+        ApplicationCommand::globals(application_id)
+            .iter()
+            .map(CommandRegister::from)
+            .collect()
+    }
+
+    #[test]
+    fn same_as_itself() {
+        for command in register("1") {
+            assert!(command.same_as(&command));
+        }
+    }
+
+    #[test]
+    fn same_as_with_discord_defaults() {
+        let local = CommandRegister::from(&ApplicationCommand {
+            dm_permission: None,
+            options: Some(vec![ApplicationCommandOption {
+                name: "entry".into(),
+                choices: Some(Vec::new()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        let mut remote = local.clone();
+        remote.r#type = Some(CommandType::ChatInput);
+        remote.dm_permission = Some(true);
+        let option = &mut remote.options.as_mut().unwrap()[0];
+        option.required = Some(false);
+        option.autocomplete = Some(false);
+        option.choices = None;
+
+        assert!(local.same_as(&remote));
+        assert!(remote.same_as(&local));
+    }
+
+    #[test]
+    fn empty_options_same_as_none() {
+        let none = CommandRegister::from(&ApplicationCommand::default());
+        let mut empty = none.clone();
+        empty.options = Some(Vec::new());
+
+        assert!(none.same_as(&empty));
+    }
+
+    #[test]
+    fn changed_definitions_differ() {
+        let original = CommandRegister::from(&ApplicationCommand::default());
+
+        let mut description = original.clone();
+        description.description = "Something else".into();
+        assert!(!original.same_as(&description));
+
+        let mut permission = original.clone();
+        permission.dm_permission = Some(false);
+        assert!(!original.same_as(&permission));
+
+        let mut option = original.clone();
+        option.options = Some(vec![ApplicationCommandOption {
+            max_length: Some(1000),
+            ..Default::default()
+        }]);
+        let mut limit = option.clone();
+        limit.options.as_mut().unwrap()[0].max_length = Some(500);
+        assert!(!option.same_as(&limit));
+        assert!(!original.same_as(&option));
+    }
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use worker::console_log;
+
+use super::{
+    ApplicationCommand, ApplicationCommandOption, Command, CommandContext, CommandResponse,
+};
+use crate::error::Respond;
+use crate::interaction::command_handler::CommandHandler;
+use crate::interaction::{CommandInteraction, CommandName, OptionType};
+use crate::locale::Locale;
+
+pub struct Entry;
+
+#[async_trait(?Send)]
+impl Command for Entry {
+    fn name(&self) -> CommandName {
+        CommandName::Entry
+    }
+
+    fn definition(&self, application_id: &str) -> ApplicationCommand {
+        let text = Locale::English.text();
+        ApplicationCommand {
+            name: self.name(),
+            name_localizations: Locale::localizations(|t| t.entry_name),
+            description: text.entry_description.into(),
+            description_localizations: Locale::localizations(|t| t.entry_description),
+            options: Some(vec![ApplicationCommandOption {
+                r#type: OptionType::String,
+                name: text.entry_option_name.into(),
+                name_localizations: Locale::localizations(|t| t.entry_option_name),
+                description: text.entry_option_description.into(),
+                description_localizations: Locale::localizations(|t| t.entry_option_description),
+                required: Some(true),
+                min_length: Some(5),
+                max_length: Some(1000),
+                ..Default::default()
+            }]),
+            application_id: application_id.to_string(),
+            dm_permission: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn deferred(&self) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> CommandResponse {
+        console_log!("Handling entry");
+        let locale = interaction.locale();
+        let mut handler = match CommandHandler::new(interaction, ctx).await {
+            Ok(handler) => handler,
+            Err(err) => return err.respond(locale).into(),
+        };
+        let entry = match interaction.entry() {
+            Ok(entry) => entry,
+            Err(err) => return err.respond(locale).into(),
+        };
+        if let Err(err) = interaction.add_entry(&handler.thankful_kv, &entry).await {
+            return err.respond(locale).into();
+        }
+        handler.handle_entry(&entry).await.into()
+    }
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use super::{ApplicationCommand, Command, CommandContext, CommandResponse};
+use crate::interaction::{CommandInteraction, CommandName, SimpleMessageResponse};
+use crate::locale::Locale;
+
+pub struct Help;
+
+#[async_trait(?Send)]
+impl Command for Help {
+    fn name(&self) -> CommandName {
+        CommandName::Help
+    }
+
+    fn definition(&self, application_id: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            name: self.name(),
+            name_localizations: Locale::localizations(|t| t.help_name),
+            application_id: application_id.to_string(),
+            description: Locale::English.text().help_description.into(),
+            description_localizations: Locale::localizations(|t| t.help_description),
+            dm_permission: Some(true),
+            ..Default::default()
+        }
+    }
+
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        _ctx: CommandContext,
+    ) -> CommandResponse {
+        SimpleMessageResponse::help(interaction.locale()).into()
+    }
+}
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use worker::{console_error, console_log};
+
+use super::{ApplicationCommand, Command, CommandContext, CommandResponse};
+use crate::error::Respond;
+use crate::interaction::{
+    CommandInteraction, CommandName, CommandType, DoubleTextInputModalData,
+    DoubleTextInputModalResponse, InteractionResponseType, PendingGratitude, SimpleMessageResponse,
+};
+use crate::locale::Locale;
+
+pub struct SendGratitude;
+
+#[async_trait(?Send)]
+impl Command for SendGratitude {
+    fn name(&self) -> CommandName {
+        CommandName::SendGratitude
+    }
+
+    fn definition(&self, application_id: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            name: self.name(),
+            name_localizations: Locale::localizations(|t| t.send_gratitude_name),
+            r#type: Some(CommandType::User),
+            application_id: application_id.to_string(),
+            dm_permission: Some(true),
+            ..Default::default()
+        }
+    }
+
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> CommandResponse {
+        console_log!("Handling send gratitude!");
+        let locale = interaction.locale();
+        let uid = match interaction.author() {
+            Ok(author) => &author.id,
+            Err(err) => return err.respond(locale).into(),
+        };
+        let target = interaction.data.target_id.as_ref().and_then(|target_id| {
+            interaction
+                .data
+                .resolved
+                .as_ref()
+                .and_then(|resolved| resolved.users.as_ref())
+                .and_then(|users| users.get(target_id))
+        });
+        let Some(target) = target else {
+            console_error!("Target user missing from user command!");
+            return SimpleMessageResponse::error(locale).into();
+        };
+        if target.id == *uid {
+            return SimpleMessageResponse::gratitude_self(locale).into();
+        }
+        if target.bot == Some(true) {
+            return SimpleMessageResponse::gratitude_bot(locale).into();
+        }
+
+        let pending = PendingGratitude {
+            target_name: target.username.clone(),
+        };
+        let key = PendingGratitude::key(uid, &target.id);
+        let put = match ctx.thankful_kv.put(&key, &pending) {
+            Ok(put) => put.expiration_ttl(PendingGratitude::TTL),
+            Err(err) => {
+                console_error!("Couldn't serialize pending gratitude: {}", err);
+                return SimpleMessageResponse::error(locale).into();
+            }
+        };
+        if let Err(err) = put.execute().await {
+            console_error!("Couldn't store pending gratitude: {}", err);
+            return SimpleMessageResponse::error(locale).into();
+        }
+
+        DoubleTextInputModalResponse {
+            r#type: InteractionResponseType::Modal,
+            data: DoubleTextInputModalData::gratitude_note(&target.id, &target.username, locale),
+        }
+        .into()
+    }
+}
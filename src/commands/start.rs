@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use super::{ApplicationCommand, Command, CommandContext, CommandResponse};
+use crate::error::Respond;
+use crate::interaction::command_handler::CommandHandler;
+use crate::interaction::{CommandInteraction, CommandName};
+use crate::locale::Locale;
+
+pub struct Start;
+
+#[async_trait(?Send)]
+impl Command for Start {
+    fn name(&self) -> CommandName {
+        CommandName::Start
+    }
+
+    fn definition(&self, application_id: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            name: self.name(),
+            name_localizations: Locale::localizations(|t| t.start_name),
+            application_id: application_id.to_string(),
+            description: Locale::English.text().start_description.into(),
+            description_localizations: Locale::localizations(|t| t.start_description),
+            dm_permission: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn deferred(&self) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> CommandResponse {
+        match CommandHandler::new(interaction, ctx).await {
+            Ok(mut handler) => handler.handle_start().await.into(),
+            Err(err) => err.respond(interaction.locale()).into(),
+        }
+    }
+}
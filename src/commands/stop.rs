@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use super::{ApplicationCommand, Command, CommandContext, CommandResponse};
+use crate::error::Respond;
+use crate::interaction::command_handler::CommandHandler;
+use crate::interaction::{CommandInteraction, CommandName};
+use crate::locale::Locale;
+
+pub struct Stop;
+
+#[async_trait(?Send)]
+impl Command for Stop {
+    fn name(&self) -> CommandName {
+        CommandName::Stop
+    }
+
+    fn definition(&self, application_id: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            name: self.name(),
+            name_localizations: Locale::localizations(|t| t.stop_name),
+            application_id: application_id.to_string(),
+            description: Locale::English.text().stop_description.into(),
+            description_localizations: Locale::localizations(|t| t.stop_description),
+            dm_permission: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn deferred(&self) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> CommandResponse {
+        match CommandHandler::new(interaction, ctx).await {
+            Ok(mut handler) => handler.handle_stop().await.into(),
+            Err(err) => err.respond(interaction.locale()).into(),
+        }
+    }
+}
@@ -1,37 +1,258 @@
-use reqwest::{header, RequestBuilder};
-use worker::{Env, Result};
+use reqwest::{header, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use worker::{console_warn, Date, Delay, Env, Result};
+
+use crate::commands::{ApplicationCommand, CommandRegister};
+use crate::interaction::{Channel, Message};
+
+mod rate_limit;
+use rate_limit::{RateLimited, RateLimits};
+
+/// Attempts per request, across rate limits and server errors.
+const MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_API_URL: &str = "https://discord.com/api";
+const DEFAULT_API_VERSION: &str = "10";
+
+/// Waiting any longer than this would risk the worker's time limit, so the
+/// request fails instead.
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// JSON error codes Discord reports, the ones the bot reacts to.
+/// https://discord.com/developers/docs/topics/opcodes-and-status-codes#json
+pub mod codes {
+    pub const UNKNOWN_CHANNEL: u32 = 10003;
+    pub const UNKNOWN_USER: u32 = 10013;
+    pub const CANNOT_MESSAGE_USER: u32 = 50007;
+}
+
 pub struct Client {
     client: reqwest::Client,
+    base_url: String,
+    limits: RateLimits,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiscordError {
+    #[error("Request to Discord failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Discord responded with status {status}: {error}")]
+    Api { status: u16, error: ApiError },
+
+    #[error("Rate limited on {route} for another {wait:?}.")]
+    RateLimited { route: String, wait: Duration },
+}
+
+/// The body Discord sends along with unsuccessful responses.
+#[derive(Debug, Default, Deserialize)]
+pub struct ApiError {
+    pub code: u32,
+    pub message: String,
+    pub errors: Option<serde_json::Value>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)?;
+        if let Some(errors) = &self.errors {
+            write!(f, ": {}", errors)?;
+        }
+        Ok(())
+    }
+}
+
+impl DiscordError {
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Self::Api { error, .. } => Some(error.code),
+            Self::Request(_) | Self::RateLimited { .. } => None,
+        }
+    }
+
+    pub fn cannot_message_user(&self) -> bool {
+        self.code() == Some(codes::CANNOT_MESSAGE_USER)
+    }
+
+    /// Errors caused by the recipient, like a closed DM or a deleted
+    /// channel, rather than by the bot's token or payload.
+    pub fn recipient_unreachable(&self) -> bool {
+        matches!(
+            self.code(),
+            Some(codes::CANNOT_MESSAGE_USER | codes::UNKNOWN_CHANNEL | codes::UNKNOWN_USER)
+        )
+    }
 }
 
 impl Client {
-    pub fn new(token: &str) -> Self {
+    pub fn new(token: &str, base_url: String) -> Self {
         let headers = Self::headers(token);
         let client = reqwest::Client::builder()
             .default_headers(headers)
             .build()
             .unwrap();
-        Self { client }
+        Self {
+            client,
+            base_url,
+            limits: RateLimits::default(),
+        }
+    }
+
+    /// `DISCORD_API_URL` and `DISCORD_API_VERSION` override where requests go,
+    /// e.g. to point the bot at a local mock of Discord.
+    pub fn from_env(env: &Env) -> Result<Self> {
+        Ok(Self::new(&token(env)?, base_url(env)))
+    }
+
+    pub async fn create_message<C: Serialize>(
+        &mut self,
+        channel_id: &str,
+        message: &Message<C>,
+    ) -> std::result::Result<(), DiscordError> {
+        let path = format!("channels/{}/messages", channel_id);
+        self.send(self.request(Method::POST, &path).json(message))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn edit_message<C: Serialize>(
+        &mut self,
+        channel_id: &str,
+        message_id: &str,
+        message: &Message<C>,
+    ) -> std::result::Result<(), DiscordError> {
+        let path = format!("channels/{}/messages/{}", channel_id, message_id);
+        self.send(self.request(Method::PATCH, &path).json(message))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn edit_original_response<C: Serialize>(
+        &mut self,
+        application_id: &str,
+        token: &str,
+        message: &Message<C>,
+    ) -> std::result::Result<(), DiscordError> {
+        let path = format!("webhooks/{}/{}/messages/@original", application_id, token);
+        self.send(self.request(Method::PATCH, &path).json(message))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_dm(&mut self, user_id: &str) -> std::result::Result<Channel, DiscordError> {
+        let payload = HashMap::from([("recipient_id", user_id)]);
+        let request = self
+            .request(Method::POST, "users/@me/channels")
+            .json(&payload);
+        self.json(request).await
     }
 
-    pub fn patch(&mut self, url: &str) -> RequestBuilder {
-        let url = format!("https://discord.com/api/{}", url);
-        self.client.patch(url)
+    pub async fn list_commands(
+        &mut self,
+        application_id: &str,
+        guild_id: Option<&str>,
+    ) -> std::result::Result<Vec<ApplicationCommand>, DiscordError> {
+        let request = self
+            .request(
+                Method::GET,
+                &ApplicationCommand::path(application_id, guild_id),
+            )
+            .query(&[("with_localizations", true)]);
+        self.json(request).await
     }
 
-    pub fn post(&mut self, url: &str) -> RequestBuilder {
-        let url = format!("https://discord.com/api/{}", url);
-        self.client.post(url)
+    pub async fn bulk_overwrite_commands(
+        &mut self,
+        application_id: &str,
+        guild_id: Option<&str>,
+        commands: &[CommandRegister],
+    ) -> std::result::Result<Vec<ApplicationCommand>, DiscordError> {
+        let request = self
+            .request(
+                Method::PUT,
+                &ApplicationCommand::path(application_id, guild_id),
+            )
+            .json(commands);
+        self.json(request).await
     }
 
-    pub fn get(&mut self, url: &str) -> RequestBuilder {
-        let url = format!("https://discord.com/api/{}", url);
-        self.client.get(url)
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/{}", self.base_url, path);
+        self.client.request(method, url)
     }
 
-    pub fn delete(&mut self, url: &str) -> RequestBuilder {
-        let url = format!("https://discord.com/api/{}", url);
-        self.client.delete(url)
+    async fn json<T: DeserializeOwned>(
+        &mut self,
+        request: RequestBuilder,
+    ) -> std::result::Result<T, DiscordError> {
+        Ok(self.send(request).await?.json::<T>().await?)
+    }
+
+    /// Sends the request, waiting out rate limits and retrying server errors
+    /// with exponential backoff.
+    async fn send(
+        &mut self,
+        request: RequestBuilder,
+    ) -> std::result::Result<Response, DiscordError> {
+        let mut request = request.build()?;
+        let route = rate_limit::route(request.method(), request.url().path());
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            if let Some(wait) = self.limits.wait_time(&route, now()) {
+                if wait > MAX_WAIT {
+                    return Err(DiscordError::RateLimited { route, wait });
+                }
+                Delay::from(wait).await;
+            }
+            self.limits.sent(now());
+
+            let retry = request.try_clone();
+            let response = self.client.execute(request).await?;
+            self.limits.update(&route, response.headers(), now());
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let body = response.text().await?;
+            let wait = if status == StatusCode::TOO_MANY_REQUESTS {
+                serde_json::from_str::<RateLimited>(&body)
+                    .ok()
+                    .map(|limited| {
+                        self.limits.limited(&route, &limited, now());
+                        Duration::ZERO
+                    })
+            } else if status.is_server_error() {
+                Some(Duration::from_millis(250 * 2u64.pow(attempt)))
+            } else {
+                None
+            };
+            match (wait, retry) {
+                (Some(wait), Some(retry)) if attempt < MAX_ATTEMPTS => {
+                    console_warn!("{} failed with {}, retrying", route, status);
+                    if !wait.is_zero() {
+                        Delay::from(wait).await;
+                    }
+                    request = retry;
+                }
+                _ => return Err(Self::error(status, body)),
+            }
+        }
+    }
+
+    fn error(status: StatusCode, body: String) -> DiscordError {
+        let error = serde_json::from_str(&body).unwrap_or_else(|_| ApiError {
+            message: body,
+            ..Default::default()
+        });
+        DiscordError::Api {
+            status: status.as_u16(),
+            error,
+        }
     }
 
     fn headers(token: &str) -> header::HeaderMap {
@@ -42,7 +263,24 @@ impl Client {
     }
 }
 
+fn base_url(env: &Env) -> String {
+    let var = |name: &str, default: &str| {
+        env.var(name)
+            .map(|var| var.to_string())
+            .unwrap_or_else(|_| default.to_string())
+    };
+    format!(
+        "{}/v{}",
+        var("DISCORD_API_URL", DEFAULT_API_URL).trim_end_matches('/'),
+        var("DISCORD_API_VERSION", DEFAULT_API_VERSION)
+    )
+}
+
 pub fn token(env: &Env) -> Result<String> {
     let discord_token = env.var("DISCORD_TOKEN")?.to_string();
     Ok("Bot ".to_string() + &discord_token)
 }
+
+fn now() -> u64 {
+    Date::now().as_millis()
+}
@@ -0,0 +1,272 @@
+//! Per-bucket and global (50 requests a second) rate limits, see
+//! https://discord.com/developers/docs/topics/rate-limits
+
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const GLOBAL_LIMIT: u32 = 50;
+const GLOBAL_WINDOW: u64 = 1000;
+
+/// Path segments after which an ID is a "major parameter", which gets its
+/// own rate limits. Other IDs share their route's limits.
+const MAJOR_PARAMETERS: [&str; 3] = ["channels", "guilds", "webhooks"];
+
+/// The body of a 429 response.
+#[derive(Debug, Deserialize)]
+pub struct RateLimited {
+    pub retry_after: f64,
+    #[serde(default)]
+    pub global: bool,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    remaining: u32,
+    /// Milliseconds since the epoch.
+    reset_at: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimits {
+    routes: HashMap<String, String>,
+    buckets: HashMap<String, Bucket>,
+    global_until: u64,
+    window_start: u64,
+    window_count: u32,
+}
+
+/// The route a request is rate limited under, e.g.
+/// `POST /api/channels/123/messages/{id}`.
+pub fn route(method: &Method, path: &str) -> String {
+    let mut previous = "";
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(|segment| {
+            let is_id = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            let major = MAJOR_PARAMETERS.contains(&previous);
+            previous = segment;
+            if is_id && !major {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("{} {}", method, segments.join("/"))
+}
+
+impl RateLimits {
+    /// How long to hold off before sending another request on `route`.
+    pub fn wait_time(&self, route: &str, now: u64) -> Option<Duration> {
+        let mut until = self.global_until;
+        if self.window_count >= GLOBAL_LIMIT {
+            until = until.max(self.window_start + GLOBAL_WINDOW);
+        }
+        let bucket = self
+            .routes
+            .get(route)
+            .and_then(|bucket| self.buckets.get(bucket));
+        if let Some(bucket) = bucket {
+            if bucket.remaining == 0 {
+                until = until.max(bucket.reset_at);
+            }
+        }
+        (until > now).then(|| Duration::from_millis(until - now))
+    }
+
+    /// Counts a request towards the global limit.
+    pub fn sent(&mut self, now: u64) {
+        if now >= self.window_start + GLOBAL_WINDOW {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+        self.window_count += 1;
+    }
+
+    /// Learns the route's bucket and its remaining requests from a response.
+    pub fn update(&mut self, route: &str, headers: &HeaderMap, now: u64) {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let Some(bucket) = header("x-ratelimit-bucket") else {
+            return;
+        };
+        let remaining = header("x-ratelimit-remaining").and_then(|value| value.parse().ok());
+        let reset_after = header("x-ratelimit-reset-after").and_then(|value| value.parse().ok());
+        if let (Some(remaining), Some(reset_after)) = (remaining, reset_after) {
+            self.routes.insert(route.to_string(), bucket.to_string());
+            self.buckets.insert(
+                bucket.to_string(),
+                Bucket {
+                    remaining,
+                    reset_at: now + seconds(reset_after),
+                },
+            );
+        }
+    }
+
+    /// Blocks the route's bucket, or everything for a global limit, until
+    /// Discord says it's fine to try again.
+    pub fn limited(&mut self, route: &str, limited: &RateLimited, now: u64) {
+        let reset_at = now + seconds(limited.retry_after);
+        if limited.global {
+            self.global_until = reset_at;
+            return;
+        }
+        let bucket = self
+            .routes
+            .entry(route.to_string())
+            .or_insert_with(|| route.to_string())
+            .clone();
+        self.buckets.insert(
+            bucket,
+            Bucket {
+                remaining: 0,
+                reset_at,
+            },
+        );
+    }
+}
+
+fn seconds(seconds: f64) -> u64 {
+    (seconds * 1000.0).ceil() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(bucket: &str, remaining: &str, reset_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("x-ratelimit-bucket", bucket),
+            ("x-ratelimit-remaining", remaining),
+            ("x-ratelimit-reset-after", reset_after),
+        ] {
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn major_parameters_kept() {
+        assert_eq!(
+            route(&Method::POST, "/api/v10/channels/123/messages"),
+            "POST /api/v10/channels/123/messages"
+        );
+        assert_eq!(
+            route(&Method::PATCH, "/api/v10/channels/123/messages/456"),
+            "PATCH /api/v10/channels/123/messages/{id}"
+        );
+        assert_eq!(
+            route(&Method::PUT, "/api/v10/applications/1/guilds/2/commands"),
+            "PUT /api/v10/applications/{id}/guilds/2/commands"
+        );
+        assert_eq!(
+            route(
+                &Method::PATCH,
+                "/api/v10/webhooks/1/token/messages/@original"
+            ),
+            "PATCH /api/v10/webhooks/1/token/messages/@original"
+        );
+    }
+
+    #[test]
+    fn other_ids_share_a_route() {
+        assert_eq!(
+            route(&Method::DELETE, "/api/v10/channels/1/messages/2"),
+            route(&Method::DELETE, "/api/v10/channels/1/messages/3")
+        );
+        assert_ne!(
+            route(&Method::POST, "/api/v10/channels/1/messages"),
+            route(&Method::POST, "/api/v10/channels/2/messages")
+        );
+    }
+
+    #[test]
+    fn waits_for_exhausted_bucket() {
+        let mut limits = RateLimits::default();
+        let route = "POST /channels/1/messages";
+        assert_eq!(limits.wait_time(route, 0), None);
+
+        limits.update(route, &headers("abc", "1", "2.5"), 1_000);
+        assert_eq!(limits.wait_time(route, 1_000), None);
+
+        limits.update(route, &headers("abc", "0", "2.5"), 1_000);
+        assert_eq!(
+            limits.wait_time(route, 2_000),
+            Some(Duration::from_millis(1_500))
+        );
+        assert_eq!(limits.wait_time(route, 3_500), None);
+        assert_eq!(limits.wait_time("POST /channels/2/messages", 2_000), None);
+    }
+
+    #[test]
+    fn routes_share_buckets() {
+        let mut limits = RateLimits::default();
+        limits.update("PATCH /a", &headers("shared", "1", "1"), 0);
+        limits.update("PATCH /b", &headers("shared", "0", "1"), 0);
+        assert_eq!(
+            limits.wait_time("PATCH /a", 0),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn incomplete_headers_ignored() {
+        let mut limits = RateLimits::default();
+        let mut partial = headers("abc", "0", "1");
+        partial.remove("x-ratelimit-reset-after");
+        limits.update("GET /a", &partial, 0);
+        assert_eq!(limits.wait_time("GET /a", 0), None);
+    }
+
+    #[test]
+    fn limited_blocks_route() {
+        let mut limits = RateLimits::default();
+        let limited = RateLimited {
+            retry_after: 0.25,
+            global: false,
+        };
+        limits.limited("GET /a", &limited, 100);
+        assert_eq!(
+            limits.wait_time("GET /a", 100),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(limits.wait_time("GET /b", 100), None);
+    }
+
+    #[test]
+    fn global_limit_blocks_everything() {
+        let mut limits = RateLimits::default();
+        let limited = RateLimited {
+            retry_after: 1.0,
+            global: true,
+        };
+        limits.limited("GET /a", &limited, 0);
+        assert_eq!(
+            limits.wait_time("GET /b", 400),
+            Some(Duration::from_millis(600))
+        );
+        assert_eq!(limits.wait_time("GET /b", 1_000), None);
+    }
+
+    #[test]
+    fn global_window() {
+        const START: u64 = 1_700_000_000_000;
+        let mut limits = RateLimits::default();
+        for _ in 0..GLOBAL_LIMIT {
+            assert_eq!(limits.wait_time("GET /a", START), None);
+            limits.sent(START);
+        }
+        assert_eq!(
+            limits.wait_time("GET /a", START),
+            Some(Duration::from_millis(GLOBAL_WINDOW))
+        );
+        assert_eq!(limits.wait_time("GET /a", START + GLOBAL_WINDOW), None);
+        limits.sent(START + GLOBAL_WINDOW);
+        assert_eq!(limits.wait_time("GET /a", START + GLOBAL_WINDOW), None);
+    }
+}
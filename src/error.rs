@@ -1,6 +1,11 @@
 use std::fmt;
+use worker::console_error;
+use worker::kv::KvError;
 
+use crate::discord::DiscordError;
 use crate::error;
+use crate::interaction::SimpleMessageResponse;
+use crate::locale::Locale;
 use crate::verification;
 
 #[derive(Debug, thiserror::Error)]
@@ -63,3 +68,54 @@ impl From<worker::Error> for Http {
         Self::from(error::General::from(error))
     }
 }
+
+/// Errors raised while handling an interaction or a scheduled run.
+#[derive(Debug, thiserror::Error)]
+pub enum Handler {
+    #[error("KV operation failed: {0}")]
+    Kv(#[from] KvError),
+
+    #[error("Discord request failed: {0}")]
+    Discord(#[from] DiscordError),
+
+    #[error("Failed to deserialize from or serialize to JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Interaction is missing its {0}.")]
+    MissingField(&'static str),
+
+    #[error("Couldn't open a DM channel with user {0}.")]
+    NoDmChannel(String),
+
+    #[error("Error sending message to user {uid}: {error}")]
+    Notify { uid: String, error: DiscordError },
+
+    #[error("User list unexpectedly empty!")]
+    NoUsers,
+
+    #[error("Length after removing not one less. Old: {before}, New: {after}")]
+    DropUser { before: usize, after: usize },
+}
+
+/// Errors that can reach a user are turned into an ephemeral reply
+/// explaining what went wrong, instead of Discord's "interaction failed".
+pub trait Respond: fmt::Display {
+    fn response(&self, locale: Locale) -> SimpleMessageResponse;
+
+    fn respond(&self, locale: Locale) -> SimpleMessageResponse {
+        console_error!("{}", self);
+        self.response(locale)
+    }
+}
+
+impl Respond for Handler {
+    fn response(&self, locale: Locale) -> SimpleMessageResponse {
+        match self {
+            Self::NoDmChannel(_) => SimpleMessageResponse::dms_closed(locale),
+            Self::Notify { error, .. } if error.cannot_message_user() => {
+                SimpleMessageResponse::dms_closed(locale)
+            }
+            _ => SimpleMessageResponse::error(locale),
+        }
+    }
+}
@@ -1,174 +1,211 @@
-use serde_json::{from_str, to_string};
-use worker::{console_error, console_log, kv::KvStore};
+use serde::Deserialize;
+use serde_json::{from_str, from_value, Value};
+use worker::kv::KvStore;
+use worker::{console_error, console_log};
 
 use crate::discord;
-use crate::users::BotUser;
+use crate::error::{self, Respond};
+use crate::journal;
+use crate::locale::Locale;
 
 pub mod data_types;
 pub use data_types::*;
 
-mod command_handler;
-use command_handler::CommandHandler;
+pub mod command_handler;
+pub mod custom_id;
+pub mod embed;
+pub mod markdown;
+pub mod processed;
 
-impl PingInteraction {
-    pub fn handle() -> InteractionResponse<NoResponseData> {
-        InteractionResponse {
-            r#type: InteractionResponseType::Pong,
-            data: None,
-        }
+impl<D, M> Interaction<D, M> {
+    pub fn locale(&self) -> Locale {
+        self.locale.unwrap_or_default()
+    }
+
+    /// The user behind the interaction, whether it came from a DM or a guild.
+    pub fn author(&self) -> Result<&User, error::Handler> {
+        self.user
+            .as_ref()
+            .or_else(|| self.member.as_ref().and_then(|member| member.user.as_ref()))
+            .ok_or(error::Handler::MissingField("user"))
     }
 }
 
-impl CommandInteraction {
-    pub async fn handle(
-        &self,
-        mut client: discord::Client,
-        users_kv: KvStore,
-        thankful_kv: KvStore,
-    ) -> SimpleMessageResponse {
-        let (uid, mut channel_id) = self.ids();
-        if channel_id.is_empty() {
-            channel_id = match self.dm_channel(&uid, &mut client).await {
-                Some(id) => id,
-                None => return SimpleMessageResponse::error(),
+impl InteractionVariants {
+    pub fn parse(body: &str) -> Result<Self, serde_json::Error> {
+        let value: Value = from_str(body)?;
+        let kind = value
+            .get("type")
+            .and_then(|kind| InteractionType::deserialize(kind).ok());
+        let custom_id = value.pointer("/data/custom_id");
+
+        let parsed = match kind {
+            Some(InteractionType::Ping) => Ok(Self::Ping),
+            Some(InteractionType::ApplicationCommand) => {
+                from_value(value.clone()).map(Self::Command)
             }
-        };
-        let users = match users_kv.get("users").json::<Vec<BotUser>>().await {
-            Ok(Some(users)) => users,
-            Ok(None) => {
-                console_error!("User list unexpectedly empty!");
-                return SimpleMessageResponse::error();
+            Some(InteractionType::ApplicationCommandAutocomplete) => Ok(Self::Autocomplete),
+            Some(InteractionType::MessageComponent) => {
+                match custom_id.and_then(|id| CustomId::deserialize(id).ok()) {
+                    Some(CustomId::GratefulButton) => from_value(value.clone()).map(Self::Button),
+                    _ => Ok(Self::unsupported(&value)),
+                }
             }
-            Err(err) => {
-                console_error!("Couldn't get list of users: {}", err);
-                return SimpleMessageResponse::error();
+            Some(InteractionType::ModalSubmit) => {
+                match custom_id.and_then(|id| ModalId::deserialize(id).ok()) {
+                    Some(ModalId::GratefulModal) => from_value(value.clone()).map(Self::Modal),
+                    Some(ModalId::GratitudeModal(_)) => {
+                        from_value(value.clone()).map(Self::GratitudeModal)
+                    }
+                    None => Ok(Self::unsupported(&value)),
+                }
             }
+            None => Ok(Self::unsupported(&value)),
         };
-        let user = BotUser { uid, channel_id };
-        let add_key = format!("ADD {}", to_string(&user).unwrap());
-        let delete_key = format!("DELETE {}", &user.uid);
-
-        let mut handler = CommandHandler {
-            user,
-            client,
-            users_kv,
-            thankful_kv,
-            add_key,
-            delete_key,
-            users,
+        Ok(parsed.unwrap_or_else(|err| {
+            console_error!("Couldn't parse interaction: {}", err);
+            Self::unsupported(&value)
+        }))
+    }
+
+    /// The ID and locale of the user behind the interaction, if any.
+    pub fn author(&self) -> Option<(String, Locale)> {
+        let (author, locale) = match self {
+            Self::Command(i) => (i.author(), i.locale()),
+            Self::Button(i) => (i.author(), i.locale()),
+            Self::Modal(i) => (i.author(), i.locale()),
+            Self::GratitudeModal(i) => (i.author(), i.locale()),
+            Self::Ping | Self::Autocomplete | Self::Unsupported(_) => return None,
         };
+        author.ok().map(|user| (user.id.clone(), locale))
+    }
 
-        match self.data.name {
-            CommandName::Start => handler.handle_start().await,
-            CommandName::Stop => handler.handle_stop().await,
-            CommandName::Entry => {
-                console_log!("Handling entry");
-                let entry = self.entry();
-                self.add_entry(&handler.thankful_kv, &entry).await;
-                handler.handle_entry(&entry).await
-            }
-            CommandName::Help => SimpleMessageResponse::help(),
+    fn unsupported(value: &Value) -> Self {
+        let locale = value
+            .get("locale")
+            .and_then(|locale| Locale::deserialize(locale).ok())
+            .unwrap_or_default();
+        Self::Unsupported(locale)
+    }
+}
+
+impl AutocompleteResponse {
+    pub fn empty() -> Self {
+        Self {
+            r#type: InteractionResponseType::AutocompleteResult,
+            data: AutocompleteData::default(),
         }
     }
+}
 
-    pub fn ids(&self) -> (String, String) {
+impl PingInteraction {
+    pub fn handle() -> InteractionResponse<NoResponseData> {
+        InteractionResponse {
+            r#type: InteractionResponseType::Pong,
+            data: None,
+        }
+    }
+}
+
+impl CommandInteraction {
+    /// The user's ID, and the ID of the DM channel if the command was used
+    /// in DMs. Guild commands leave the channel ID empty.
+    pub fn ids(&self) -> Result<(String, String), error::Handler> {
         match self.user.as_ref() {
             Some(User { id, .. }) => {
-                let user_id = id.clone();
                 let channel_id = self
                     .channel_id
                     .clone()
-                    //TODO: Make illegal state unrepresentable
-                    .expect("If user struct is there, channel_id will be the DM channel");
-                (user_id, channel_id)
+                    .ok_or(error::Handler::MissingField("channel ID"))?;
+                Ok((id.clone(), channel_id))
             }
-            None => (
-                self.member
-                    .as_ref()
-                    .unwrap()
-                    .user
-                    .as_ref()
-                    .unwrap()
-                    .id
-                    .clone(),
-                String::new(),
-            ),
+            None => Ok((self.author()?.id.clone(), String::new())),
         }
     }
 
-    pub async fn dm_channel(&self, user_id: &str, client: &mut discord::Client) -> Option<String> {
-        let mut channel_payload = std::collections::HashMap::new();
-        channel_payload.insert("recipient_id", user_id);
-        let response = client
-            .post("users/@me/channels")
-            .json(&channel_payload)
-            .send()
-            .await;
-        let channel = match response {
-            Ok(response) => response.json::<Channel>().await,
-            Err(err) => {
-                console_error!("Couldn't get DM channel: {}", err);
-                return None;
-            }
-        };
-        match channel {
-            Ok(channel) => Some(channel.id),
-            Err(err) => {
-                console_error!("Couldn't get DM channel: {}", err);
-                None
+    pub fn entry(&self) -> Result<String, error::Handler> {
+        self.data
+            .options()
+            .string("entry")
+            .map(str::to_string)
+            .ok_or(error::Handler::MissingField("entry option"))
+    }
+
+    pub async fn add_entry(
+        &self,
+        thankful_kv: &KvStore,
+        entry: &str,
+    ) -> Result<(), error::Handler> {
+        add_journal_entry(thankful_kv, &self.author()?.id, entry).await
+    }
+}
+
+impl ApplicationCommandData {
+    pub fn options(&self) -> CommandOptions<'_> {
+        let mut path = Vec::new();
+        let mut options = self.options.as_deref().unwrap_or_default();
+        while let [option] = options {
+            match option.r#type {
+                OptionType::SubCommand | OptionType::SubCommandGroup => {
+                    path.push(option.name.as_str());
+                    options = option.options.as_deref().unwrap_or_default();
+                }
+                _ => break,
             }
         }
+        CommandOptions { path, options }
     }
+}
 
-    fn entry(&self) -> String {
-        let OptionData { value, .. } = self.data.options.as_ref().unwrap().first().unwrap();
-        let OptionValue::String(ref value) = value.as_ref().unwrap() else { unreachable!("Value guaranteed by Discord") };
-        value.clone()
+impl CommandOptions<'_> {
+    pub fn get(&self, name: &str) -> Option<&OptionValue> {
+        self.options
+            .iter()
+            .find(|option| option.name == name)
+            .and_then(|option| option.value.as_ref())
     }
 
-    async fn add_entry(&self, thankful_kv: &KvStore, entry: &str) {
-        let id = match self.user.as_ref() {
-            Some(User { id, .. }) => id,
-            None => match self.member {
-                Some(Member { ref user, .. }) => &user.as_ref().unwrap().id,
-                None => unreachable!("There should always be a member or a user!"),
-            },
-        };
-        let mut entries = self.get_entries(thankful_kv, id).await;
-        entries.push(entry.to_string());
-        thankful_kv
-            .put(id, entries)
-            .unwrap()
-            .execute()
-            .await
-            .expect("should be able to serialize entries");
-    }
-
-    async fn get_entries(&self, kv: &KvStore, id: &str) -> Vec<String> {
-        match kv.get(id).text().await {
-            Ok(Some(text)) => from_str(&text).unwrap(),
-            Ok(None) => Vec::new(),
-            Err(err) => {
-                console_error!("Couldn't get entries: {}", err);
-                panic!();
-            }
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            OptionValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))] // No command takes one yet.
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            OptionValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))] // No command takes one yet.
+    pub fn number(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            OptionValue::Integer(value) => Some(*value as f64),
+            OptionValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))] // No command takes one yet.
+    pub fn boolean(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            OptionValue::Bool(value) => Some(*value),
+            _ => None,
         }
     }
 }
 
 impl ButtonInteraction {
-    pub fn handle_grateful(&self) -> SingleTextInputModalResponse {
-        let name = self
-            .user
-            .clone()
-            //TODO: Make illegal state unrepresentable
-            .expect("Only users can click buttons")
-            .username;
+    pub fn handle_grateful(&self) -> Result<SingleTextInputModalResponse, error::Handler> {
+        let name = &self.author()?.username;
         console_log!("Handling button!");
-        SingleTextInputModalResponse {
+        Ok(SingleTextInputModalResponse {
             r#type: InteractionResponseType::Modal,
-            data: ModalResponse::with_name(&name),
-        }
+            data: ModalResponse::with_name(name, self.locale()),
+        })
     }
 }
 
@@ -178,309 +215,491 @@ impl SingleTextModalButtonInteraction {
         thankful_kv: KvStore,
         client: discord::Client,
     ) -> SimpleMessageResponse {
-        self.add_entry(thankful_kv).await;
-        self.disable_button(client).await;
+        if let Err(err) = self.add_entry(&thankful_kv).await {
+            return err.respond(self.locale());
+        }
+        if let Err(err) = self.disable_button(client).await {
+            console_error!("Error disabling button: {}", err);
+        }
 
         SimpleMessageResponse {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage {
-                content: Some(format!(
-                    "**You added the following entry:**\n{}",
-                    self.entry()
-                )),
-                ..Default::default()
-            },
+            data: NoComponentMessage::from_entry(self.entry(), self.locale()),
         }
     }
 
-    async fn add_entry(&self, thankful_kv: KvStore) {
-        let entry = self.entry();
-        let id = match self.user.as_ref() {
-            Some(User { id, .. }) => id,
-            None => match self.member {
-                Some(Member { ref user, .. }) => &user.as_ref().unwrap().id,
-                None => unreachable!("There should always be a member or a user!"),
-            },
+    async fn add_entry(&self, thankful_kv: &KvStore) -> Result<(), error::Handler> {
+        add_journal_entry(thankful_kv, &self.author()?.id, self.entry()).await
+    }
+
+    fn entry(&self) -> &str {
+        &self.data.components[0].components[0].value
+    }
+
+    async fn disable_button(&mut self, client: discord::Client) -> Result<(), error::Handler> {
+        self.message.components[0].components[0].disabled = Some(true);
+        self.submit_disable_button_request(client).await
+    }
+
+    async fn submit_disable_button_request(
+        &self,
+        mut client: discord::Client,
+    ) -> Result<(), error::Handler> {
+        let channel_id = self
+            .channel_id
+            .as_ref()
+            .ok_or(error::Handler::MissingField("channel ID"))?;
+        let message_id = self
+            .message
+            .id
+            .as_ref()
+            .ok_or(error::Handler::MissingField("message ID"))?;
+        client
+            .edit_message(channel_id, message_id, &self.message)
+            .await?;
+        Ok(())
+    }
+}
+
+impl GratitudeModalInteraction {
+    pub async fn handle(
+        &self,
+        thankful_kv: KvStore,
+        mut client: discord::Client,
+    ) -> SimpleMessageResponse {
+        console_log!("Handling gratitude modal!");
+        let locale = self.locale();
+        let sender = match self.author() {
+            Ok(sender) => sender,
+            Err(err) => return err.respond(locale),
         };
-        let mut entries = self.get_entries(&thankful_kv, id).await;
-        entries.push(entry.to_string());
-        thankful_kv
-            .put(id, entries)
-            .unwrap()
-            .execute()
-            .await
-            .expect("should be able to serialize entries");
-    }
-
-    async fn get_entries(&self, kv: &KvStore, id: &str) -> Vec<String> {
-        match kv.get(id).text().await {
-            Ok(Some(text)) => from_str(&text).unwrap(),
-            Ok(None) => Vec::new(),
+        let ModalId::GratitudeModal(target_id) = &self.data.custom_id else {
+            console_error!("Gratitude modal without a target!");
+            return SimpleMessageResponse::error(locale);
+        };
+        // The name is only needed for the sender's journal, so a modal left
+        // open past the TTL falls back to a mention.
+        let key = PendingGratitude::key(&sender.id, target_id);
+        let target_name = match thankful_kv.get(&key).json::<PendingGratitude>().await {
+            Ok(Some(pending)) => pending.target_name,
+            Ok(None) => format!("<@{}>", target_id),
             Err(err) => {
-                console_error!("Couldn't get entries: {}", err);
-                panic!();
+                console_error!("Couldn't get pending gratitude: {}", err);
+                format!("<@{}>", target_id)
             }
+        };
+
+        let note = self.note();
+        let from = if self.anonymous() {
+            None
+        } else {
+            Some(sender.username.as_str())
+        };
+        let Some(channel_id) = dm_channel(target_id, &mut client).await else {
+            return SimpleMessageResponse::gratitude_undeliverable(locale);
+        };
+        let payload = NoComponentMessage::gratitude_note(from, note, locale);
+        if let Err(err) = client.create_message(&channel_id, &payload).await {
+            console_error!("Couldn't deliver gratitude to {}: {}", target_id, err);
+            return SimpleMessageResponse::gratitude_undeliverable(locale);
         }
+
+        let text = locale.text();
+        let given = format!(
+            "{} {}",
+            text.gratitude_given_entry.replace("{name}", &target_name),
+            note
+        );
+        if let Err(err) = journal::append(&thankful_kv, &sender.id, &given).await {
+            console_error!("Couldn't record given gratitude: {}", err);
+        }
+        let received = format!(
+            "{} {}",
+            text.gratitude_received_entry
+                .replace("{name}", from.unwrap_or(text.gratitude_someone)),
+            note
+        );
+        if let Err(err) = journal::append(&thankful_kv, target_id, &received).await {
+            console_error!("Couldn't record received gratitude: {}", err);
+        }
+
+        if let Err(err) = thankful_kv.delete(&key).await {
+            console_error!("Couldn't remove pending gratitude: {}", err);
+        }
+        SimpleMessageResponse::gratitude_sent(locale)
     }
 
-    fn entry(&self) -> &str {
+    fn note(&self) -> &str {
         &self.data.components[0].components[0].value
     }
 
-    async fn disable_button(&mut self, client: discord::Client) {
-        self.message.components[0].components[0].disabled = Some(true);
-        self.submit_disable_button_request(client).await;
+    fn anonymous(&self) -> bool {
+        let answer = self.data.components[1].components[0].value.trim();
+        self.locale()
+            .text()
+            .gratitude_yes
+            .contains(&answer.to_lowercase().as_str())
     }
+}
 
-    async fn submit_disable_button_request(&self, mut client: discord::Client) {
-        if let Err(error) = client
-            .patch(&format!(
-                "channels/{}/messages/{}",
-                self.channel_id.as_ref().unwrap(),
-                self.message.id.as_ref().unwrap()
-            ))
-            .json(&self.message)
-            .send()
-            .await
-            .unwrap()
-            .error_for_status()
-        {
-            console_error!("Error disabling button: {}", error);
+impl PendingGratitude {
+    pub const TTL: u64 = 15 * 60;
+
+    /// One per sender and target, so notes to several people can be
+    /// written at the same time.
+    pub fn key(uid: &str, target_id: &str) -> String {
+        format!("GRATITUDE {} {}", uid, target_id)
+    }
+}
+
+pub async fn dm_channel(user_id: &str, client: &mut discord::Client) -> Option<String> {
+    match client.create_dm(user_id).await {
+        Ok(channel) => Some(channel.id),
+        Err(err) => {
+            console_error!("Couldn't get DM channel: {}", err);
+            None
         }
     }
 }
 
+/// Adds an entry the user wrote themselves, collapsing accidental repeats.
+async fn add_journal_entry(kv: &KvStore, id: &str, entry: &str) -> Result<(), error::Handler> {
+    if processed::repeated_entry(kv, id, entry).await? {
+        console_log!("Collapsed repeated entry of user {}", id);
+        return Ok(());
+    }
+    journal::append(kv, id, entry).await?;
+    if let Err(err) = processed::remember_entry(kv, id, entry).await {
+        console_error!("Couldn't remember last entry of user {}: {}", id, err);
+    }
+    Ok(())
+}
+
 impl SimpleMessageResponse {
     #[allow(dead_code)]
-    fn not_implemented() -> Self {
+    pub fn not_implemented(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::not_implemented(),
+            data: NoComponentMessage::not_implemented(locale),
         }
     }
 
-    fn help() -> Self {
+    pub fn deferred(ephemeral: bool) -> Self {
+        Self {
+            r#type: InteractionResponseType::ACKWithSource,
+            data: NoComponentMessage {
+                flags: ephemeral.then_some(1 << 6),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn unsupported(locale: Locale) -> Self {
+        Self {
+            r#type: InteractionResponseType::ChannelMessageWithSource,
+            data: NoComponentMessage::unsupported(locale),
+        }
+    }
+
+    pub fn help(locale: Locale) -> Self {
+        Self {
+            r#type: InteractionResponseType::ChannelMessageWithSource,
+            data: NoComponentMessage::help(locale),
+        }
+    }
+
+    pub fn success(locale: Locale) -> Self {
+        Self {
+            r#type: InteractionResponseType::ChannelMessageWithSource,
+            data: NoComponentMessage::success(locale),
+        }
+    }
+
+    pub fn error(locale: Locale) -> Self {
+        Self {
+            r#type: InteractionResponseType::ChannelMessageWithSource,
+            data: NoComponentMessage::error(locale),
+        }
+    }
+
+    pub fn dms_closed(locale: Locale) -> Self {
+        Self {
+            r#type: InteractionResponseType::ChannelMessageWithSource,
+            data: NoComponentMessage::dms_closed(locale),
+        }
+    }
+
+    pub fn already_active(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::help(),
+            data: NoComponentMessage::already_active(locale),
         }
     }
 
-    fn success() -> Self {
+    pub fn not_active(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::success(),
+            data: NoComponentMessage::not_active(locale),
         }
     }
 
-    fn error() -> Self {
+    pub fn gratitude_sent(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::error(),
+            data: NoComponentMessage::gratitude_sent(locale),
         }
     }
 
-    fn dms_closed() -> Self {
+    pub fn gratitude_self(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::dms_closed(),
+            data: NoComponentMessage::gratitude_self(locale),
         }
     }
 
-    fn already_active() -> Self {
+    pub fn gratitude_bot(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::already_active(),
+            data: NoComponentMessage::gratitude_bot(locale),
         }
     }
 
-    fn not_active() -> Self {
+    pub fn gratitude_undeliverable(locale: Locale) -> Self {
         Self {
             r#type: InteractionResponseType::ChannelMessageWithSource,
-            data: NoComponentMessage::not_active(),
+            data: NoComponentMessage::gratitude_undeliverable(locale),
         }
     }
 }
 
 impl SingleTextInputModalData {
-    pub fn with_name(name: &str) -> Self {
+    pub fn with_name(name: &str, locale: Locale) -> Self {
         Self {
             custom_id: ModalId::GratefulModal,
-            title: format!("{}'s Gratitude Journal", name),
-            components: [SingleTextInputActionRow::with_text_entry()],
+            title: locale.text().journal_title.replace("{name}", name),
+            components: [SingleTextInputActionRow::with_text_entry(locale)],
+        }
+    }
+}
+
+impl DoubleTextInputModalData {
+    pub fn gratitude_note(target_id: &str, name: &str, locale: Locale) -> Self {
+        Self {
+            custom_id: ModalId::GratitudeModal(target_id.to_string()),
+            title: locale.text().gratitude_title.replace("{name}", name),
+            components: [
+                SingleTextInputActionRow {
+                    r#type: ActionRowType::ActionRow,
+                    components: [TextInput::gratitude_note(locale)],
+                },
+                SingleTextInputActionRow {
+                    r#type: ActionRowType::ActionRow,
+                    components: [TextInput::gratitude_anonymous(locale)],
+                },
+            ],
         }
     }
 }
 
 impl TextInput {
-    pub fn new() -> Self {
+    pub fn new(locale: Locale) -> Self {
         TextInput {
             r#type: ModalComponentType::TextInput,
             custom_id: TextInputId::GratefulInput,
             style: 2,
-            label: "Express your gratitude for something!".into(),
+            label: locale.text().entry_input_label.into(),
             min_length: 5,
             max_length: 1000,
-            placeholder:
-                "Today, I am grateful for… (a nice meal, someone smiling at me, how I perfectly parked my car)"
-                    .to_string(),
+            required: Some(true),
+            placeholder: locale.text().entry_input_placeholder.into(),
+        }
+    }
+
+    pub fn gratitude_note(locale: Locale) -> Self {
+        TextInput {
+            r#type: ModalComponentType::TextInput,
+            custom_id: TextInputId::GratitudeNote,
+            style: 2,
+            label: locale.text().gratitude_note_label.into(),
+            min_length: 5,
+            max_length: 1000,
+            required: Some(true),
+            placeholder: locale.text().gratitude_note_placeholder.into(),
+        }
+    }
+
+    pub fn gratitude_anonymous(locale: Locale) -> Self {
+        TextInput {
+            r#type: ModalComponentType::TextInput,
+            custom_id: TextInputId::GratitudeAnonymous,
+            style: 1,
+            label: locale.text().gratitude_anonymous_label.into(),
+            min_length: 0,
+            max_length: 4,
+            required: Some(false),
+            placeholder: locale.text().gratitude_anonymous_placeholder.into(),
         }
     }
 }
 
 impl SingleButtonMessage {
-    pub fn welcome() -> Self {
-        let content = Some(
-            concat!(
-                "**Hi there! Thank you for deciding to use Gratitude Bot! 🥳**\n",
-                "The bot will send you reminders, every few days or so, to ",
-                "think about someting you are grateful for, and ask you to add it ",
-                "to your journal! You can use /stop at any time to stop these reminders",
-                "\n\n👇 Click the button below to make an entry into your journal right now!"
-            )
-            .to_string(),
-        );
+    pub fn welcome(locale: Locale) -> Self {
         SingleButtonMessage {
-            content,
-            components: [SingleButtonActionRow::entry_button()],
+            content: Some(locale.text().welcome.to_string()),
+            components: [SingleButtonActionRow::entry_button(locale)],
             ..Default::default()
         }
     }
 
-    pub fn from_entry(journal_entry: Option<String>) -> Self {
-        let content = match journal_entry {
-            Some(text) => Some(format!(
-                "__**Here's something you said you were grateful for in the past:**__\n{}",
-                text
-            )),
-            None => Some("Hope you're having a great day!".into()),
+    pub fn from_entry(journal_entry: Option<String>, locale: Locale) -> Self {
+        let (content, embeds) = match journal_entry {
+            Some(text) => (
+                None,
+                Some(vec![Embed::new()
+                    .title(locale.text().past_entry)
+                    .quote(&text)]),
+            ),
+            None => (Some(locale.text().no_past_entry.into()), None),
         };
         Self {
             content,
-            components: [SingleButtonActionRow::entry_button()],
+            embeds,
+            components: [SingleButtonActionRow::entry_button(locale)],
             ..Default::default()
         }
     }
 }
 
 impl NoComponentMessage {
-    pub fn from_entry(entry: &str) -> Self {
+    pub fn from_entry(entry: &str, locale: Locale) -> Self {
         NoComponentMessage {
-            content: Some(format!("__**You added the following entry:**__\n{}", entry)),
+            embeds: Some(vec![Embed::new()
+                .title(locale.text().entry_added)
+                .quote(entry)
+                .now()]),
             ..Default::default()
         }
     }
-    pub fn not_implemented() -> Self {
+
+    pub fn not_implemented(locale: Locale) -> Self {
         Self {
-            content: Some("This command is not yet implemented! Coming soon!".into()),
+            content: Some(locale.text().not_implemented.into()),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn goodbye() -> Self {
-        let content = Some(
-            concat!(
-                "**You will no longer receive reminders! See you around! 😊**\n",
-                "Rememeber that you can still use **/entry** to make entries, ",
-                "and **/start** to receive these reminders again!"
-            )
-            .into(),
-        );
+    pub fn unsupported(locale: Locale) -> Self {
         Self {
-            content,
+            content: Some(locale.text().unsupported.into()),
+            flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn help() -> Self {
-        Self {
-            content: Some(
-                concat!(
-                    "__**Welcome to Gratitude Bot!**__\n",
-                    "*This bot makes you focus on the positive things in life!*\n\n",
-                    "It does this by randomly, once every few days on average, nudging ",
-                    "you to add an entry to the gratitude journal it keeps for you, while ",
-                    "reminding you of things you said you were grateful for in the past. ",
-                    "Anything goes here: The smallest thing that made you smile today, or ",
-                    "that big event that changed your life last month. Over time, your ",
-                    "brain will change to be more aware of the nice things in life, help ",
-                    "you appreciate what you have right now!\n\nYou can use **/start** to ",
-                    "sign up for those reminders, **/stop** to stop receiving them, and ",
-                    "**/entry** to add something to the journal at any point!\n\n",
-                    "*The bot is open source, and you can view (and copy!) the code ",
-                    "right here: <https://github.com/Fittiboy/gratitude>!*\n\n",
-                    "The inspiration for this bot came in great part from this video ",
-                    "by Kurzgesagt: https://youtu.be/WPPPFqsECz0",
-                )
-                .to_string(),
-            ),
+    pub fn paused(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().paused.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn goodbye(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().goodbye.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn help(locale: Locale) -> Self {
+        Self {
+            embeds: Some(vec![Embed::new()
+                .title(locale.text().help_title)
+                .description(locale.text().help)]),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn success() -> Self {
+    pub fn success(locale: Locale) -> Self {
         Self {
-            content: Some(
-                "**It looks like that worked! 🥳** If it didn't do what you expected, contact Fitti#6969"
-                    .to_string(),
-            ),
+            embeds: Some(vec![Embed::new().description(locale.text().success)]),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn error() -> Self {
+    pub fn error(locale: Locale) -> Self {
         Self {
-            content: Some(
-                "Oh no! It looks like something went wrong!\nAsk Fitti#6969 for help!".into(),
-            ),
+            content: Some(locale.text().error.into()),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn dms_closed() -> Self {
+    pub fn dms_closed(locale: Locale) -> Self {
         Self {
-            content: Some(format!(
-                "It looks like the bot can't DM you! Check your privacy settings: {}",
-                "https://support.discord.com/hc/en-us/articles/217916488-Blocking-Privacy-Settings",
-            )),
+            content: Some(locale.text().dms_closed.into()),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn already_active() -> Self {
+    pub fn already_active(locale: Locale) -> Self {
         Self {
-            content: Some(
-                concat!(
-                    "Looks like you're already an active user! ",
-                    "The bot will randomly send you reminders every few days.\n",
-                    "Use the **/stop** command to stop receiving those reminders! ",
-                    "Remember that you can use **/entry** to add something to your ",
-                    "journal at any time!"
-                )
-                .to_string(),
-            ),
+            content: Some(locale.text().already_active.into()),
             flags: Some(1 << 6),
             ..Default::default()
         }
     }
 
-    pub fn not_active() -> Self {
+    pub fn gratitude_note(from: Option<&str>, note: &str, locale: Locale) -> Self {
+        let text = locale.text();
+        let sender = from.unwrap_or(text.gratitude_someone);
         Self {
-            content: Some(
-                concat!(
-                    "Looks like you're not an active user! ",
-                    "The bot will not send you reminders.\n",
-                    "Use the **/start** command to start receiving those reminders! ",
-                    "Remember that you can always use **/entry** to add something to ",
-                    "your journal!"
-                )
-                .to_string(),
-            ),
+            embeds: Some(vec![Embed::new()
+                .title(&text.gratitude_received.replace("{name}", sender))
+                .quote(note)
+                .now()]),
+            ..Default::default()
+        }
+    }
+
+    pub fn gratitude_sent(locale: Locale) -> Self {
+        Self {
+            embeds: Some(vec![Embed::new().description(locale.text().gratitude_sent)]),
+            flags: Some(1 << 6),
+            ..Default::default()
+        }
+    }
+
+    pub fn gratitude_self(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().gratitude_self.into()),
+            flags: Some(1 << 6),
+            ..Default::default()
+        }
+    }
+
+    pub fn gratitude_bot(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().gratitude_bot.into()),
+            flags: Some(1 << 6),
+            ..Default::default()
+        }
+    }
+
+    pub fn gratitude_undeliverable(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().gratitude_undeliverable.into()),
+            flags: Some(1 << 6),
+            ..Default::default()
+        }
+    }
+
+    pub fn not_active(locale: Locale) -> Self {
+        Self {
+            content: Some(locale.text().not_active.into()),
             flags: Some(1 << 6),
             ..Default::default()
         }
@@ -488,31 +707,93 @@ impl NoComponentMessage {
 }
 
 impl SingleButtonActionRow {
-    fn entry_button() -> Self {
+    fn entry_button(locale: Locale) -> Self {
         Self {
             r#type: ActionRowType::ActionRow,
-            components: [Button::entry()],
+            components: [Button::entry(locale)],
         }
     }
 }
 
 impl SingleTextInputActionRow {
-    fn with_text_entry() -> Self {
+    fn with_text_entry(locale: Locale) -> Self {
         Self {
             r#type: ActionRowType::ActionRow,
-            components: [TextInput::new()],
+            components: [TextInput::new(locale)],
         }
     }
 }
 
 impl Button {
-    fn entry() -> Self {
+    fn entry(locale: Locale) -> Self {
         Button {
             r#type: InteractionComponentType::Button,
             style: 3,
-            label: "What are you grateful for today?".into(),
+            label: locale.text().entry_button.into(),
             custom_id: CustomId::GratefulButton,
             disabled: Some(false),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ApplicationCommandData;
+
+    fn data(options: &str) -> ApplicationCommandData {
+        ApplicationCommandData {
+            options: Some(serde_json::from_str(options).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn top_level_options() {
+        let data = data(r#"[{"name": "entry", "type": 3, "value": "Sunshine"}]"#);
+        let options = data.options();
+        assert!(options.path.is_empty());
+        assert_eq!(options.string("entry"), Some("Sunshine"));
+        assert_eq!(options.integer("entry"), None);
+        assert_eq!(options.string("missing"), None);
+    }
+
+    #[test]
+    fn subcommand() {
+        let without_options = data(r#"[{"name": "list", "type": 1}]"#);
+        let options = without_options.options();
+        assert_eq!(options.path, ["list"]);
+        assert!(options.options.is_empty());
+
+        let with_empty_options = data(r#"[{"name": "list", "type": 1, "options": []}]"#);
+        assert_eq!(with_empty_options.options().path, ["list"]);
+    }
+
+    #[test]
+    fn subcommand_group_with_typed_options() {
+        let data = data(
+            r#"[{
+                "name": "reminders",
+                "type": 2,
+                "options": [{
+                    "name": "set",
+                    "type": 1,
+                    "options": [
+                        {"name": "hours", "type": 4, "value": 12},
+                        {"name": "chance", "type": 10, "value": 0.25},
+                        {"name": "weight", "type": 10, "value": 2},
+                        {"name": "weekends", "type": 5, "value": false}
+                    ]
+                }]
+            }]"#,
+        );
+        let options = data.options();
+        assert_eq!(options.path, ["reminders", "set"]);
+        assert_eq!(options.integer("hours"), Some(12));
+        assert_eq!(options.number("hours"), Some(12.0));
+        assert_eq!(options.number("chance"), Some(0.25));
+        assert_eq!(options.integer("chance"), None);
+        assert_eq!(options.number("weight"), Some(2.0));
+        assert_eq!(options.boolean("weekends"), Some(false));
+        assert_eq!(options.string("weekends"), None);
+    }
+}
@@ -1,9 +1,13 @@
+use crate::commands::CommandContext;
 use crate::discord;
-use crate::interaction::{Message, NoComponentMessage, SimpleMessageResponse};
-use crate::users::BotUser;
-use std::fmt;
+use crate::error::{self, Respond};
+use crate::interaction::{
+    dm_channel, CommandInteraction, Message, NoComponentMessage, SimpleMessageResponse,
+};
+use crate::users::{self, BotUser};
+use serde_json::to_string;
+use worker::console_log;
 use worker::kv::{KvError, KvStore};
-use worker::{console_error, console_log};
 
 pub struct CommandHandler {
     //TODO: Add ApplicationCommandData for /entry
@@ -12,80 +16,125 @@ pub struct CommandHandler {
     pub users_kv: KvStore,
     pub thankful_kv: KvStore,
     pub add_key: String,
+    pub add_prefix: String,
     pub delete_key: String,
     pub users: Vec<BotUser>,
 }
 
 impl CommandHandler {
+    pub async fn new(
+        interaction: &CommandInteraction,
+        ctx: CommandContext,
+    ) -> Result<Self, error::Handler> {
+        let CommandContext {
+            mut client,
+            users_kv,
+            thankful_kv,
+        } = ctx;
+        let locale = interaction.locale();
+        let (uid, mut channel_id) = interaction.ids()?;
+        if channel_id.is_empty() {
+            channel_id = dm_channel(&uid, &mut client)
+                .await
+                .ok_or_else(|| error::Handler::NoDmChannel(uid.clone()))?;
+        };
+        let users = users_kv
+            .get("users")
+            .json::<Vec<BotUser>>()
+            .await?
+            .ok_or(error::Handler::NoUsers)?;
+        let user = BotUser {
+            uid,
+            channel_id,
+            locale,
+            ..Default::default()
+        };
+        let add_key = format!("ADD {}", to_string(&user)?);
+        // `uid` serialises first, so this matches the user's pending add
+        // whatever locale or channel it was made from.
+        let add_prefix = format!("ADD {{\"uid\":{},", to_string(&user.uid)?);
+        let delete_key = format!("DELETE {}", &user.uid);
+
+        Ok(Self {
+            user,
+            client,
+            users_kv,
+            thankful_kv,
+            add_key,
+            add_prefix,
+            delete_key,
+            users,
+        })
+    }
+
     pub async fn handle_start(&mut self) -> SimpleMessageResponse {
         console_log!("Handling start!");
-        if self.delete_present().await {
-            if let Err(err) = self.remove_delete().await {
-                console_error!("Couldn't remove delete key from kv: {}", err);
-                return SimpleMessageResponse::error();
-            }
-        } else if self.already_active().await {
-            return SimpleMessageResponse::already_active();
-        } else if let Err(err) = self.insert_add().await {
-            console_error!("Couldn't add user to list: {}", err);
-            return SimpleMessageResponse::error();
-        }
-        if let Err(error) = self.notify_start().await {
-            console_error!("{}", error.to_string());
-            return SimpleMessageResponse::dms_closed();
+        self.start()
+            .await
+            .unwrap_or_else(|err| err.respond(self.user.locale))
+    }
+
+    async fn start(&mut self) -> Result<SimpleMessageResponse, error::Handler> {
+        if self.delete_present().await? {
+            self.remove_delete().await?;
+        } else if self.already_active().await? {
+            return Ok(SimpleMessageResponse::already_active(self.user.locale));
+        } else {
+            self.insert_add().await?;
         }
+        users::clear_paused(&self.thankful_kv, &self.user.uid).await?;
+        self.notify_start().await?;
         console_log!("New user: {:?}", self.user.uid);
 
-        SimpleMessageResponse::success()
+        Ok(SimpleMessageResponse::success(self.user.locale))
     }
 
     pub async fn handle_stop(&mut self) -> SimpleMessageResponse {
         console_log!("Handling stop!");
-        if self.add_present().await {
-            if let Err(err) = self.remove_add().await {
-                console_error!("Couldn't remove delete key from kv: {}", err);
-                return SimpleMessageResponse::error();
-            }
-        } else if self.not_active().await {
-            return SimpleMessageResponse::not_active();
-        } else if (self.drop_user()).is_err() {
-            return SimpleMessageResponse::error();
-        } else if let Err(err) = self.insert_delete().await {
-            console_error!("Couldn't remove user from list: {}", err);
-            return SimpleMessageResponse::error();
-        }
-        if (self.notify_stop().await).is_err() {
-            return SimpleMessageResponse::dms_closed();
+        self.stop()
+            .await
+            .unwrap_or_else(|err| err.respond(self.user.locale))
+    }
+
+    async fn stop(&mut self) -> Result<SimpleMessageResponse, error::Handler> {
+        if self.add_present().await? {
+            self.remove_add().await?;
+        } else if self.not_active().await? {
+            return Ok(SimpleMessageResponse::not_active(self.user.locale));
+        } else {
+            self.drop_user()?;
+            self.insert_delete().await?;
         }
+        self.notify_stop().await?;
         console_log!("User removed: {:?}", self.user.uid);
 
-        SimpleMessageResponse::success()
+        Ok(SimpleMessageResponse::success(self.user.locale))
     }
 
     pub async fn handle_entry(&mut self, entry: &str) -> SimpleMessageResponse {
-        if (self.notify_entry(entry).await).is_err() {
-            return SimpleMessageResponse::dms_closed();
+        match self.notify_entry(entry).await {
+            Ok(()) => SimpleMessageResponse::success(self.user.locale),
+            Err(err) => err.respond(self.user.locale),
         }
+    }
 
-        SimpleMessageResponse::success()
+    pub async fn delete_present(&self) -> Result<bool, KvError> {
+        Ok(self.users_kv.get(&self.delete_key).text().await?.is_some())
     }
 
-    pub async fn delete_present(&self) -> bool {
-        self.users_kv
-            .get(&self.delete_key)
-            .text()
-            .await
-            .unwrap()
-            .is_some()
+    pub async fn add_present(&self) -> Result<bool, KvError> {
+        Ok(!self.pending_adds().await?.is_empty())
     }
 
-    pub async fn add_present(&self) -> bool {
-        self.users_kv
-            .get(&self.add_key)
-            .text()
-            .await
-            .unwrap()
-            .is_some()
+    async fn pending_adds(&self) -> Result<Vec<String>, KvError> {
+        let list = self.users_kv.list().prefix(self.add_prefix.clone());
+        Ok(list
+            .execute()
+            .await?
+            .keys
+            .into_iter()
+            .map(|key| key.name)
+            .collect())
     }
 
     pub async fn remove_delete(&self) -> Result<(), KvError> {
@@ -93,115 +142,71 @@ impl CommandHandler {
     }
 
     pub async fn remove_add(&self) -> Result<(), KvError> {
-        self.users_kv.delete(&self.add_key).await
+        for key in self.pending_adds().await? {
+            self.users_kv.delete(&key).await?;
+        }
+        Ok(())
     }
 
     pub async fn insert_delete(&self) -> Result<(), KvError> {
-        self.users_kv
-            .put(&self.delete_key, "POOF")
-            .unwrap()
-            .execute()
-            .await
+        self.users_kv.put(&self.delete_key, "POOF")?.execute().await
     }
 
     pub async fn insert_add(&self) -> Result<(), KvError> {
-        self.users_kv
-            .put(&self.add_key, "FOOP")
-            .unwrap()
-            .execute()
-            .await
+        self.users_kv.put(&self.add_key, "FOOP")?.execute().await
     }
 
-    pub async fn already_active(&self) -> bool {
-        self.users.iter().any(|user| user.uid == self.user.uid)
-            || self
-                .users_kv
-                .get(&self.add_key)
-                .text()
-                .await
-                .unwrap()
-                .is_some()
+    /// Paused users count as inactive, so that `/start` resumes their
+    /// reminders.
+    pub async fn already_active(&self) -> Result<bool, KvError> {
+        Ok(self
+            .users
+            .iter()
+            .any(|user| user.uid == self.user.uid && user.paused.is_none())
+            || self.add_present().await?)
     }
 
-    pub async fn not_active(&self) -> bool {
-        !self.users.iter().any(|user| user.uid == self.user.uid)
-            || self
-                .users_kv
-                .get(&self.delete_key)
-                .text()
-                .await
-                .unwrap()
-                .is_some()
+    pub async fn not_active(&self) -> Result<bool, KvError> {
+        Ok(!self.users.iter().any(|user| user.uid == self.user.uid)
+            || self.delete_present().await?)
     }
 
-    pub fn drop_user(&mut self) -> Result<(), HandlerError> {
+    pub fn drop_user(&mut self) -> Result<(), error::Handler> {
         let before = self.users.len();
         self.users.retain(|user| user.uid != self.user.uid);
         let after = self.users.len();
         if before - 1 == after {
             Ok(())
         } else {
-            Err(HandlerError::DropUser { before, after })
+            Err(error::Handler::DropUser { before, after })
         }
     }
 
-    pub async fn notify_start(&mut self) -> Result<(), HandlerError<'_>> {
-        let payload = Message::welcome();
+    pub async fn notify_start(&mut self) -> Result<(), error::Handler> {
+        let payload = Message::welcome(self.user.locale);
         self.notify(payload).await
     }
 
-    pub async fn notify_stop(&mut self) -> Result<(), HandlerError<'_>> {
-        let payload = Message::goodbye();
+    pub async fn notify_stop(&mut self) -> Result<(), error::Handler> {
+        let payload = Message::goodbye(self.user.locale);
         self.notify(payload).await
     }
 
-    pub async fn notify_entry(&mut self, entry: &str) -> Result<(), HandlerError<'_>> {
-        let payload = NoComponentMessage::from_entry(entry);
+    pub async fn notify_entry(&mut self, entry: &str) -> Result<(), error::Handler> {
+        let payload = NoComponentMessage::from_entry(entry, self.user.locale);
         self.notify(payload).await
     }
 
-    pub async fn notify<T>(&mut self, payload: Message<T>) -> Result<(), HandlerError<'_>>
+    pub async fn notify<T>(&mut self, payload: Message<T>) -> Result<(), error::Handler>
     where
         T: serde::Serialize,
     {
-        let client = self
-            .client
-            .post(&format!("channels/{}/messages", self.user.channel_id))
-            .json(&payload);
-        if let Err(error) = client.send().await.unwrap().error_for_status() {
-            let error = HandlerError::Notify {
-                uid: &self.user.uid,
-                error: error.to_string(),
-            };
-            console_error!("{}", error.to_string());
-            Err(error)
-        } else {
-            Ok(())
-        }
-    }
-}
-
-#[derive(Debug)]
-pub enum HandlerError<'a> {
-    DropUser { before: usize, after: usize },
-    Notify { uid: &'a str, error: String },
-}
-
-impl fmt::Display for HandlerError<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::DropUser { before, after } => {
-                write!(
-                    f,
-                    "Length after removing not one less. Old: {}, New: {}",
-                    before, after
-                )
-            }
-            Self::Notify { uid, error } => {
-                write!(f, "Error sending message to user {}: {}", uid, error)
-            }
-        }
+        self.client
+            .create_message(&self.user.channel_id, &payload)
+            .await
+            .map_err(|error| error::Handler::Notify {
+                uid: self.user.uid.clone(),
+                error,
+            })
     }
 }
-
-impl std::error::Error for HandlerError<'_> {}
@@ -0,0 +1,287 @@
+//! Custom IDs carry state between interactions, encoded as a name and its
+//! parameters separated by colons, like `gratitude_modal:<target_id>`.
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display};
+use std::str::{FromStr, Split};
+
+use crate::interaction::{CustomId, EntryAction, Hours, ModalId, TextInputId};
+
+/// Discord rejects custom IDs longer than this.
+pub const MAX_LENGTH: usize = 100;
+const SEPARATOR: char = ':';
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Custom ID is {0} characters long, but at most {MAX_LENGTH} are allowed.")]
+    TooLong(usize),
+
+    #[error("Parameter '{0}' must not contain '{SEPARATOR}'.")]
+    InvalidParameter(String),
+
+    #[error("Unknown custom ID '{0}'.")]
+    Unknown(String),
+
+    #[error("Custom ID '{0}' has missing or malformed parameters.")]
+    Malformed(String),
+}
+
+pub trait Codec: Sized {
+    fn encode(&self) -> Result<String, Error>;
+    fn decode(id: &str) -> Result<Self, Error>;
+}
+
+/// Joins a name and its parameters into a custom ID.
+pub fn encode(name: &str, params: &[&dyn Display]) -> Result<String, Error> {
+    let mut id = name.to_string();
+    for param in params {
+        let param = param.to_string();
+        if param.contains(SEPARATOR) {
+            return Err(Error::InvalidParameter(param));
+        }
+        id.push(SEPARATOR);
+        id.push_str(&param);
+    }
+    check_length(&id)?;
+    Ok(id)
+}
+
+fn check_length(id: &str) -> Result<(), Error> {
+    match id.chars().count() {
+        length if length > MAX_LENGTH => Err(Error::TooLong(length)),
+        _ => Ok(()),
+    }
+}
+
+/// Splits a custom ID into its name and parameters, which are then read
+/// one at a time, in order.
+pub struct Decoder<'a> {
+    id: &'a str,
+    pub name: &'a str,
+    params: Split<'a, char>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(id: &'a str) -> Result<Self, Error> {
+        check_length(id)?;
+        let mut params = id.split(SEPARATOR);
+        let name = params.next().unwrap_or_default();
+        Ok(Self { id, name, params })
+    }
+
+    pub fn param<T: FromStr>(&mut self) -> Result<T, Error> {
+        self.params
+            .next()
+            .and_then(|param| param.parse().ok())
+            .ok_or_else(|| Error::Malformed(self.id.to_string()))
+    }
+
+    /// Makes sure no parameters are left over before handing out the result.
+    pub fn finish<T>(mut self, decoded: T) -> Result<T, Error> {
+        match self.params.next() {
+            Some(_) => Err(Error::Malformed(self.id.to_string())),
+            None => Ok(decoded),
+        }
+    }
+
+    pub fn unknown<T>(&self) -> Result<T, Error> {
+        Err(Error::Unknown(self.id.to_string()))
+    }
+}
+
+impl Codec for CustomId {
+    fn encode(&self) -> Result<String, Error> {
+        match self {
+            Self::GratefulButton => encode("grateful_button", &[]),
+            Self::Page(page) => encode("page", &[page]),
+            Self::Entry(entry_id, action) => encode("entry", &[entry_id, action]),
+            Self::Snooze(hours) => encode("snooze", &[hours]),
+        }
+    }
+
+    fn decode(id: &str) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(id)?;
+        let custom_id = match decoder.name {
+            "grateful_button" => Self::GratefulButton,
+            "page" => Self::Page(decoder.param()?),
+            "entry" => Self::Entry(decoder.param()?, decoder.param()?),
+            "snooze" => Self::Snooze(decoder.param()?),
+            _ => return decoder.unknown(),
+        };
+        decoder.finish(custom_id)
+    }
+}
+
+impl Codec for ModalId {
+    fn encode(&self) -> Result<String, Error> {
+        match self {
+            Self::GratefulModal => encode("grateful_modal", &[]),
+            Self::GratitudeModal(target_id) => encode("gratitude_modal", &[target_id]),
+        }
+    }
+
+    fn decode(id: &str) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(id)?;
+        let custom_id = match decoder.name {
+            "grateful_modal" => Self::GratefulModal,
+            "gratitude_modal" => Self::GratitudeModal(decoder.param()?),
+            _ => return decoder.unknown(),
+        };
+        decoder.finish(custom_id)
+    }
+}
+
+impl Codec for TextInputId {
+    fn encode(&self) -> Result<String, Error> {
+        match self {
+            Self::GratefulInput => encode("grateful_input", &[]),
+            Self::GratitudeNote => encode("gratitude_note", &[]),
+            Self::GratitudeAnonymous => encode("gratitude_anonymous", &[]),
+        }
+    }
+
+    fn decode(id: &str) -> Result<Self, Error> {
+        let decoder = Decoder::new(id)?;
+        let custom_id = match decoder.name {
+            "grateful_input" => Self::GratefulInput,
+            "gratitude_note" => Self::GratitudeNote,
+            "gratitude_anonymous" => Self::GratitudeAnonymous,
+            _ => return decoder.unknown(),
+        };
+        decoder.finish(custom_id)
+    }
+}
+
+impl Display for EntryAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+impl FromStr for EntryAction {
+    type Err = Error;
+
+    fn from_str(action: &str) -> Result<Self, Self::Err> {
+        match action {
+            "delete" => Ok(Self::Delete),
+            _ => Err(Error::Unknown(action.to_string())),
+        }
+    }
+}
+
+impl Display for Hours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}h", self.0)
+    }
+}
+
+impl FromStr for Hours {
+    type Err = Error;
+
+    fn from_str(hours: &str) -> Result<Self, Self::Err> {
+        hours
+            .strip_suffix('h')
+            .and_then(|hours| hours.parse().ok())
+            .map(Self)
+            .ok_or_else(|| Error::Malformed(hours.to_string()))
+    }
+}
+
+/// Custom IDs travel as plain strings, so serde goes through the codec.
+macro_rules! serde_via_codec {
+    ($($id:ty),*) => {$(
+        impl Serialize for $id {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.encode().map_err(ser::Error::custom)?)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $id {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = String::deserialize(deserializer)?;
+                Self::decode(&id).map_err(de::Error::custom)
+            }
+        }
+    )*};
+}
+
+serde_via_codec!(CustomId, ModalId, TextInputId);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(id: CustomId, encoded: &str) {
+        assert_eq!(id.encode().unwrap(), encoded);
+        assert_eq!(CustomId::decode(encoded).unwrap(), id);
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(CustomId::GratefulButton, "grateful_button");
+        round_trip(CustomId::Page(3), "page:3");
+        round_trip(
+            CustomId::Entry("0001681234567890-000042".into(), EntryAction::Delete),
+            "entry:0001681234567890-000042:delete",
+        );
+        round_trip(CustomId::Snooze(Hours(4)), "snooze:4h");
+        let modal = ModalId::GratitudeModal("123456789012345678".into());
+        assert_eq!(ModalId::decode(&modal.encode().unwrap()).unwrap(), modal);
+    }
+
+    #[test]
+    fn length_limit() {
+        let fits = "x".repeat(MAX_LENGTH - "entry::delete".len());
+        let id = CustomId::Entry(fits.clone(), EntryAction::Delete);
+        assert_eq!(id.encode().unwrap().len(), MAX_LENGTH);
+
+        let id = CustomId::Entry(fits + "x", EntryAction::Delete);
+        assert!(matches!(id.encode(), Err(Error::TooLong(101))));
+        let encoded = format!("page:{}", "1".repeat(MAX_LENGTH - 4));
+        assert!(matches!(
+            CustomId::decode(&encoded),
+            Err(Error::TooLong(101))
+        ));
+    }
+
+    #[test]
+    fn separator_in_parameter() {
+        let id = CustomId::Entry("a:b".into(), EntryAction::Delete);
+        assert!(matches!(id.encode(), Err(Error::InvalidParameter(param)) if param == "a:b"));
+    }
+
+    #[test]
+    fn malformed() {
+        for id in [
+            "page",
+            "page:three",
+            "page:3:4",
+            "snooze:4",
+            "entry:1:delete:now",
+        ] {
+            assert!(
+                matches!(CustomId::decode(id), Err(Error::Malformed(_))),
+                "{}",
+                id
+            );
+        }
+        assert!(matches!(
+            ModalId::decode("grateful_modal:extra"),
+            Err(Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(matches!(
+            CustomId::decode("nonsense:1"),
+            Err(Error::Unknown(_))
+        ));
+        assert!(matches!(
+            CustomId::decode("entry:1:archive"),
+            Err(Error::Malformed(_))
+        ));
+    }
+}
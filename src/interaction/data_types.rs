@@ -1,11 +1,15 @@
 use crate::error::General;
+use crate::locale::Locale;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
 
 pub type PingInteraction = Interaction<PingData, NoMessage>;
 pub type CommandInteraction = Interaction<ApplicationCommandData, NoMessage>;
 pub type ButtonInteraction = SingleComponentInteraction<Button>;
 pub type SingleTextModalButtonInteraction = SingleTextModalComponentInteraction<Button>;
+pub type GratitudeModalInteraction =
+    Interaction<DoubleComponentModalSubmit<TextInputSubmit>, NoMessage>;
 
 pub type NoComponent = Option<()>;
 pub type NoMessage = Option<()>;
@@ -22,32 +26,37 @@ pub type SingleComponentModalInteraction<C, C2> =
 
 pub type SingleTextInputModalResponse = InteractionResponse<SingleTextInputModalData>;
 pub type SimpleMessageResponse = InteractionResponse<NoComponentMessage>;
+pub type AutocompleteResponse = InteractionResponse<AutocompleteData>;
+pub type DoubleTextInputModalResponse = InteractionResponse<DoubleTextInputModalData>;
 
 pub type SingleButtonActionRow = SingleComponentActionRow<Button>;
 pub type SingleTextInputActionRow = SingleComponentActionRow<TextInput>;
 pub type SingleTextInputModalData = SingleComponentModalResponse<TextInput>;
+pub type DoubleTextInputModalData = DoubleComponentModalResponse<TextInput>;
 
 pub type SingleComponentActionRow<C> = ActionRow<[C; 1]>;
 pub type SingleComponentModalResponse<C> = ModalResponse<[SingleComponentActionRow<C>; 1]>;
 pub type SingleComponentModalSubmit<C> = ModalSubmitData<[SingleComponentActionRow<C>; 1]>;
+pub type DoubleComponentModalResponse<C> = ModalResponse<[SingleComponentActionRow<C>; 2]>;
+pub type DoubleComponentModalSubmit<C> = ModalSubmitData<[SingleComponentActionRow<C>; 2]>;
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(untagged)]
+/// Interactions are told apart by their `type`, and components and modals
+/// further by their custom ID. Anything else ends up as `Unsupported`.
+#[derive(Debug, Default)]
 pub enum InteractionVariants {
-    Ping(PingInteraction),
+    #[default]
+    Ping,
     Command(CommandInteraction),
+    Autocomplete,
     Button(ButtonInteraction),
     Modal(SingleTextModalButtonInteraction),
-}
-
-impl Default for InteractionVariants {
-    fn default() -> Self {
-        Self::Ping(PingInteraction::default())
-    }
+    GratitudeModal(GratitudeModalInteraction),
+    Unsupported(Locale),
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Interaction<D, M> {
+    pub id: String,
     pub r#type: InteractionType,
     pub data: D,
     pub token: String,
@@ -56,6 +65,7 @@ pub struct Interaction<D, M> {
     pub message: M,
     pub member: Option<Member>,
     pub user: Option<User>,
+    pub locale: Option<Locale>,
 }
 
 #[derive(Debug, Default, Deserialize_repr, Serialize_repr)]
@@ -65,6 +75,7 @@ pub enum InteractionType {
     Ping = 1,
     ApplicationCommand = 2,
     MessageComponent = 3,
+    ApplicationCommandAutocomplete = 4,
     ModalSubmit = 5,
 }
 
@@ -76,28 +87,40 @@ pub struct ComponentIdData {
     pub custom_id: CustomId,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone)]
-#[allow(clippy::enum_variant_names)]
+/// Encoded as described in `custom_id`, e.g. `entry:<id>:delete`.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum CustomId {
     #[default]
-    #[serde(rename = "grateful_button")]
     GratefulButton,
+    Page(u32),
+    Entry(String, EntryAction),
+    Snooze(Hours),
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryAction {
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hours(pub u32);
+
+#[derive(Debug, Default, Clone, PartialEq)]
 #[allow(clippy::enum_variant_names)]
 pub enum ModalId {
     #[default]
-    #[serde(rename = "grateful_modal")]
     GratefulModal,
+    /// Carries the ID of the user the note is for.
+    GratitudeModal(String),
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 #[allow(clippy::enum_variant_names)]
 pub enum TextInputId {
     #[default]
-    #[serde(rename = "grateful_input")]
     GratefulInput,
+    GratitudeNote,
+    GratitudeAnonymous,
 }
 
 #[derive(Debug, Default, Deserialize_repr, Serialize_repr, Clone)]
@@ -150,6 +173,7 @@ pub struct TextInput {
     pub label: String,
     pub min_length: u32,
     pub max_length: u32,
+    pub required: Option<bool>,
     pub placeholder: String,
 }
 
@@ -169,6 +193,12 @@ pub struct ApplicationCommandData {
     pub options: Option<Vec<OptionData>>,
     pub guild_id: Option<String>,
     pub target_id: Option<String>,
+    pub resolved: Option<ResolvedData>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct ResolvedData {
+    pub users: Option<HashMap<String, User>>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
@@ -182,9 +212,11 @@ pub enum CommandName {
     Stop,
     #[serde(rename = "entry")]
     Entry,
+    #[serde(rename = "Send gratitude")]
+    SendGratitude,
 }
 
-#[derive(Debug, Default, Deserialize_repr, Serialize_repr, Clone)]
+#[derive(Debug, Default, Deserialize_repr, Serialize_repr, Clone, PartialEq)]
 #[repr(u8)]
 pub enum CommandType {
     #[default]
@@ -200,9 +232,19 @@ pub struct OptionData {
     pub r#type: OptionType,
     pub value: Option<OptionValue>,
     pub options: Option<Vec<OptionData>>,
+    pub focused: Option<bool>,
 }
 
-#[derive(Debug, Default, Deserialize_repr, Serialize_repr, Clone)]
+/// The options of the subcommand that was invoked, along with the path of
+/// subcommand group and subcommand names leading to it.
+#[derive(Debug, Default, Clone)]
+pub struct CommandOptions<'a> {
+    #[cfg_attr(not(test), allow(dead_code))] // No command has subcommands yet.
+    pub path: Vec<&'a str>,
+    pub options: &'a [OptionData],
+}
+
+#[derive(Debug, Default, Deserialize_repr, Serialize_repr, Clone, PartialEq)]
 #[repr(u8)]
 pub enum OptionType {
     SubCommand = 1,
@@ -219,15 +261,22 @@ pub enum OptionType {
     Attachment = 11,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum OptionValue {
     r#String(String),
-    Integer(u32),
+    Integer(i64),
     Float(f64),
     Bool(bool),
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OptionChoice {
+    pub name: String,
+    pub name_localizations: Option<HashMap<String, String>>,
+    pub value: OptionValue,
+}
+
 impl Default for OptionValue {
     fn default() -> Self {
         Self::r#String(String::default())
@@ -255,6 +304,12 @@ pub struct User {
     pub id: String,
     pub username: String,
     pub discriminator: String,
+    pub bot: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct PendingGratitude {
+    pub target_name: String,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -270,9 +325,15 @@ pub enum InteractionResponseType {
     Pong = 1,
     ChannelMessageWithSource = 4,
     ACKWithSource = 5,
+    AutocompleteResult = 8,
     Modal = 9,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AutocompleteData {
+    pub choices: Vec<OptionChoice>,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct ModalResponse<C> {
     pub custom_id: ModalId,
@@ -285,8 +346,49 @@ pub struct Message<C> {
     pub id: Option<String>,
     pub channel_id: Option<String>,
     pub content: Option<String>,
+    pub embeds: Option<Vec<Embed>>,
     pub flags: Option<u16>,
     pub components: C,
+    #[serde(default)]
+    pub allowed_mentions: AllowedMentions,
+}
+
+/// Defaults to allowing no mentions at all, so that entries echoed back by
+/// the bot can never ping anyone.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AllowedMentions {
+    pub parse: Vec<MentionType>,
+    pub users: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionType {
+    Roles,
+    Users,
+    Everyone,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct Embed {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub fields: Option<Vec<EmbedField>>,
+    pub color: Option<u32>,
+    pub footer: Option<EmbedFooter>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct EmbedFooter {
+    pub text: String,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -296,7 +398,7 @@ pub struct Channel {
     pub guild_id: Option<String>,
 }
 
-#[derive(Debug, Default, Serialize_repr, Deserialize_repr, Clone)]
+#[derive(Debug, Default, Serialize_repr, Deserialize_repr, Clone, PartialEq)]
 #[repr(u8)]
 pub enum ChannelType {
     #[default]
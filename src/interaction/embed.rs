@@ -0,0 +1,70 @@
+use worker::js_sys;
+use worker::Date;
+
+use crate::interaction::markdown::sanitise;
+use crate::interaction::{Embed, EmbedField, EmbedFooter};
+
+/// The warm orange all of the bot's embeds are marked with.
+pub const COLOUR: u32 = 0xF4A261;
+
+#[allow(dead_code)]
+impl Embed {
+    pub fn new() -> Self {
+        Self {
+            color: Some(COLOUR),
+            ..Default::default()
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the description to `text`, sanitised and quoted line by line, so
+    /// entries keep their own line breaks.
+    pub fn quote(self, text: &str) -> Self {
+        let quoted: Vec<String> = sanitise(text)
+            .lines()
+            .map(|line| format!("> {}", line))
+            .collect();
+        self.description(&quoted.join("\n"))
+    }
+
+    pub fn field(mut self, name: &str, value: &str, inline: bool) -> Self {
+        self.fields.get_or_insert_with(Vec::new).push(EmbedField {
+            name: name.to_string(),
+            value: value.to_string(),
+            inline: Some(inline),
+        });
+        self
+    }
+
+    pub fn colour(mut self, colour: u32) -> Self {
+        self.color = Some(colour);
+        self
+    }
+
+    pub fn footer(mut self, text: &str) -> Self {
+        self.footer = Some(EmbedFooter {
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// Timestamps are sent as ISO 8601, which Discord shows in the reader's
+    /// own timezone.
+    pub fn timestamp(mut self, date: Date) -> Self {
+        self.timestamp = Some(js_sys::Date::from(date).to_iso_string().into());
+        self
+    }
+
+    pub fn now(self) -> Self {
+        self.timestamp(Date::now())
+    }
+}
@@ -0,0 +1,80 @@
+/// Characters that would let an entry break out of the formatting around it.
+const ESCAPED: [char; 3] = ['\\', '`', '|'];
+
+/// Markers that turn a whole line into a heading, subtext, quote or list.
+const LINE_MARKERS: [&str; 4] = ["#", "-#", ">", "- "];
+
+/// Mentions that would notify everyone in a channel, in case a message ever
+/// ends up allowing them.
+const MASS_MENTIONS: [&str; 2] = ["@everyone", "@here"];
+
+/// Makes user-written text safe to embed in the bot's own markdown.
+pub fn sanitise(text: &str) -> String {
+    text.lines()
+        .map(sanitise_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn sanitise_line(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        if ESCAPED.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    let trimmed = escaped.trim_start();
+    if LINE_MARKERS
+        .iter()
+        .any(|marker| trimmed.starts_with(marker))
+    {
+        let indent = escaped.len() - trimmed.len();
+        escaped.insert(indent, '\\');
+    }
+
+    MASS_MENTIONS.iter().fold(escaped, |line, mention| {
+        line.replace(mention, &mention.replacen('@', "@\u{200b}", 1))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitise;
+
+    #[test]
+    fn plain_text_unchanged() {
+        let text = "Grateful for *sunshine* and a **good** coffee!\nAnd my cat.";
+        assert_eq!(sanitise(text), text);
+    }
+
+    #[test]
+    fn escapes_formatting_breakers() {
+        assert_eq!(sanitise("```rust"), "\\`\\`\\`rust");
+        assert_eq!(sanitise("||spoiler"), "\\|\\|spoiler");
+        assert_eq!(sanitise("back\\slash"), "back\\\\slash");
+    }
+
+    #[test]
+    fn escapes_line_markers() {
+        assert_eq!(sanitise("# Heading"), "\\# Heading");
+        assert_eq!(sanitise("-# subtext"), "\\-# subtext");
+        assert_eq!(sanitise("> quote"), "\\> quote");
+        assert_eq!(sanitise("  - item"), "  \\- item");
+        assert_eq!(sanitise("first\n> second"), "first\n\\> second");
+        assert_eq!(sanitise("a - b > c # d"), "a - b > c # d");
+    }
+
+    #[test]
+    fn escaped_marker_not_doubled() {
+        assert_eq!(sanitise("\\# not a heading"), "\\\\# not a heading");
+    }
+
+    #[test]
+    fn neutralises_mass_mentions() {
+        assert_eq!(sanitise("hi @everyone"), "hi @\u{200b}everyone");
+        assert_eq!(sanitise("@here @here"), "@\u{200b}here @\u{200b}here");
+        assert_eq!(sanitise("<@123>"), "<@123>");
+    }
+}
@@ -0,0 +1,78 @@
+//! Records interactions with side effects, so that repeats get the original
+//! response instead of running again.
+
+use serde::{Deserialize, Serialize};
+use worker::kv::{KvError, KvStore};
+
+use crate::interaction::SimpleMessageResponse;
+
+/// Interaction tokens expire after 15 minutes, so nothing older can be
+/// retried.
+const TTL: u64 = 15 * 60;
+
+/// Near-duplicate entries from the same user within this window are
+/// collapsed into one. This is also the shortest TTL KV supports.
+const DUPLICATE_WINDOW: u64 = 60;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "state", content = "response")]
+pub enum Record {
+    Pending,
+    Done(SimpleMessageResponse),
+}
+
+fn key(interaction_id: &str) -> String {
+    format!("INTERACTION {}", interaction_id)
+}
+
+fn last_entry_key(uid: &str) -> String {
+    format!("LAST ENTRY {}", uid)
+}
+
+pub async fn lookup(kv: &KvStore, interaction_id: &str) -> Result<Option<Record>, KvError> {
+    kv.get(&key(interaction_id)).json::<Record>().await
+}
+
+pub async fn start(kv: &KvStore, interaction_id: &str) -> Result<(), KvError> {
+    record(kv, interaction_id, &Record::Pending).await
+}
+
+pub async fn finish(
+    kv: &KvStore,
+    interaction_id: &str,
+    response: SimpleMessageResponse,
+) -> Result<(), KvError> {
+    record(kv, interaction_id, &Record::Done(response)).await
+}
+
+async fn record(kv: &KvStore, interaction_id: &str, record: &Record) -> Result<(), KvError> {
+    kv.put(&key(interaction_id), record)?
+        .expiration_ttl(TTL)
+        .execute()
+        .await
+}
+
+/// Tells whether `entry` is a near-duplicate of the user's latest one.
+pub async fn repeated_entry(kv: &KvStore, uid: &str, entry: &str) -> Result<bool, KvError> {
+    let last = kv.get(&last_entry_key(uid)).text().await?;
+    Ok(last.as_deref() == Some(normalized(entry).as_str()))
+}
+
+/// Remembers `entry` as the user's latest one, once it has been stored.
+pub async fn remember_entry(kv: &KvStore, uid: &str, entry: &str) -> Result<(), KvError> {
+    kv.put(&last_entry_key(uid), normalized(entry))?
+        .expiration_ttl(DUPLICATE_WINDOW)
+        .execute()
+        .await
+}
+
+/// Ignores case, whitespace and trailing punctuation, which is what tends
+/// to differ between two submissions of the same thought.
+fn normalized(entry: &str) -> String {
+    entry
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
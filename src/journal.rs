@@ -0,0 +1,75 @@
+//! Journal entries are stored under `uid:entry_id`, with IDs sorting by
+//! time, so concurrent adds can't clobber each other.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use worker::kv::KvStore;
+use worker::{console_log, Date};
+
+use crate::error;
+
+fn prefix(uid: &str) -> String {
+    format!("{}:", uid)
+}
+
+/// Milliseconds since the epoch, and a random suffix for entries written
+/// in the same millisecond.
+fn entry_id(millis: u64, suffix: u32) -> String {
+    format!("{:013}-{:06}", millis, suffix)
+}
+
+pub async fn append(kv: &KvStore, uid: &str, entry: &str) -> Result<(), error::Handler> {
+    migrate(kv, uid).await?;
+    let id = entry_id(
+        Date::now().as_millis(),
+        rand::thread_rng().gen_range(0..1_000_000),
+    );
+    kv.put(&format!("{}{}", prefix(uid), id), entry)?
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// The keys of all of the user's entries, oldest first.
+pub async fn keys(kv: &KvStore, uid: &str) -> Result<Vec<String>, error::Handler> {
+    let mut keys = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut list = kv.list().prefix(prefix(uid));
+        if let Some(cursor) = cursor {
+            list = list.cursor(cursor);
+        }
+        let page = list.execute().await?;
+        keys.extend(page.keys.into_iter().map(|key| key.name));
+        if page.list_complete {
+            return Ok(keys);
+        }
+        cursor = page.cursor;
+    }
+}
+
+pub async fn random(kv: &KvStore, uid: &str) -> Result<Option<String>, error::Handler> {
+    migrate(kv, uid).await?;
+    let keys = keys(kv, uid).await?;
+    let Some(key) = keys.choose(&mut rand::thread_rng()) else {
+        return Ok(None);
+    };
+    Ok(kv.get(key).text().await?)
+}
+
+/// Splits a journal stored as a single JSON list under the user's ID into
+/// entries. IDs come from list positions, so a rerun is safe.
+pub async fn migrate(kv: &KvStore, uid: &str) -> Result<(), error::Handler> {
+    let Some(entries) = kv.get(uid).json::<Vec<String>>().await? else {
+        return Ok(());
+    };
+    for (position, entry) in entries.iter().enumerate() {
+        let id = entry_id(0, position as u32);
+        kv.put(&format!("{}{}", prefix(uid), id), entry)?
+            .execute()
+            .await?;
+    }
+    kv.delete(uid).await?;
+    console_log!("Migrated {} entries of user {}", entries.len(), uid);
+    Ok(())
+}
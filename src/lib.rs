@@ -1,24 +1,29 @@
 #![allow(clippy::missing_panics_doc, clippy::missing_errors_doc)]
+use worker::kv::KvStore;
 use worker::{
     console_error, console_log, event, Context, Env, Request, Response, Result, Router,
     ScheduleContext, ScheduledEvent,
 };
 
+mod admin;
 mod bot;
 mod commands;
 mod discord;
 mod error;
 mod interaction;
+mod journal;
+mod locale;
+mod status;
 mod users;
 mod utils;
 mod verification;
 
 #[event(fetch)]
-pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
+pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
     utils::set_panic_hook();
     utils::log_request(&req);
 
-    let router = Router::new();
+    let router = Router::with_data(ctx);
     router
         .get_async("/", |_, _| async move {
             let url = reqwest::Url::parse(concat!(
@@ -29,6 +34,8 @@ pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
             ))?;
             Response::redirect_with_status(url, 308)
         })
+        .get_async("/health", status::health)
+        .get_async("/status", status::status)
         .post_async("/", |req, ctx| async move {
             let mut app = bot::App::new(req, ctx);
 
@@ -44,26 +51,81 @@ pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
                 }
             }
         })
+        .post_async("/admin/commands/sync", |req, ctx| {
+            admin::guard(req, ctx, admin::sync_commands)
+        })
+        .get_async("/admin/prompt/dry-run", |req, ctx| {
+            admin::guard(req, ctx, admin::dry_run)
+        })
+        .post_async("/admin/users/:uid/prompt", |req, ctx| {
+            admin::guard(req, ctx, admin::prompt)
+        })
+        .get_async("/admin/counts", |req, ctx| {
+            admin::guard(req, ctx, admin::counts)
+        })
         .run(req, env)
         .await
 }
 
+/// Commands are synced once a day, on the second schedule in `wrangler.toml`.
+/// Every other run sends reminders.
+const SYNC_COMMANDS_CRON: &str = "30 3 * * *";
+
+/// Reminder runs happen every five minutes. Users are only drawn for a
+/// reminder on the hour, and the runs in between work through the queue.
+fn on_the_hour(event: &ScheduledEvent) -> bool {
+    (event.schedule() as u64) % (60 * 60 * 1000) == 0
+}
+
 #[event(scheduled)]
 pub async fn scheduled(event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    let mut client = match discord::Client::from_env(&env) {
+        Ok(client) => client,
+        Err(err) => {
+            console_error!("Couldn't create Discord client: {}", err);
+            return;
+        }
+    };
+    let thankful_kv = env
+        .kv("thankful")
+        .expect("Worker should have access to thankful binding");
+
+    match event.cron().as_str() {
+        SYNC_COMMANDS_CRON | "TEST" => {
+            commands::update(&env, &mut client, &thankful_kv, false).await;
+        }
+        _ => remind(&env, &mut client, &thankful_kv, on_the_hour(&event)).await,
+    }
+}
+
+async fn remind(env: &Env, client: &mut discord::Client, entries_kv: &KvStore, draw: bool) {
     let users_kv = env
         .kv("grateful_users")
         .expect("Worker should have access to grateful_users binding");
-    let mut users = users::registered(&users_kv).await;
-    users::update(&mut users, &users_kv).await;
-
-    let token = discord::token(&env).unwrap();
-    let mut client = discord::Client::new(&token);
-    commands::update(&env, &mut client).await;
+    let mut users = match users::registered(&users_kv).await {
+        Ok(users) => users,
+        Err(err) => {
+            console_error!("Couldn't get registered users: {}", err);
+            return;
+        }
+    };
+    // Applied every run, so users who `/stop` while queued aren't reminded.
+    if let Err(err) = users::update(&mut users, &users_kv).await {
+        console_error!("Couldn't update users: {}", err);
+        return;
+    }
+    if draw {
+        if let Err(err) = users::enqueue(entries_kv, users::draw(&users)).await {
+            console_error!("Couldn't queue reminders: {}", err);
+            return;
+        }
+    }
 
-    if event.cron() != *"TEST" {
-        let entries_kv = env
-            .kv("thankful")
-            .expect("Worker should have access to thankful binding");
-        users::prompt(&users, &entries_kv, &mut client).await;
+    let batch_size = users::batch_size(env);
+    if let Err(err) = users::prompt(&mut users, entries_kv, client, batch_size).await {
+        console_error!("Couldn't send reminders: {}", err);
+        return;
     }
+    users::save(&users, &users_kv).await;
+    status::record(entries_kv, status::Job::Reminders).await;
 }
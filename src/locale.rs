@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+mod de;
+mod en;
+mod es;
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    Spanish,
+}
+
+impl Locale {
+    /// Discord locale codes that receive a translation, in addition to the
+    /// English default.
+    const TRANSLATED: [(&'static str, Locale); 3] = [
+        ("de", Locale::German),
+        ("es-ES", Locale::Spanish),
+        ("es-419", Locale::Spanish),
+    ];
+
+    pub fn text(self) -> &'static Catalogue {
+        match self {
+            Self::English => &en::CATALOGUE,
+            Self::German => &de::CATALOGUE,
+            Self::Spanish => &es::CATALOGUE,
+        }
+    }
+
+    pub fn localizations(text: fn(&Catalogue) -> &'static str) -> Option<HashMap<String, String>> {
+        Some(
+            Self::TRANSLATED
+                .iter()
+                .map(|(code, locale)| (code.to_string(), text(locale.text()).to_string()))
+                .collect(),
+        )
+    }
+}
+
+impl From<String> for Locale {
+    fn from(locale: String) -> Self {
+        match locale.as_str() {
+            "de" => Self::German,
+            "es-ES" | "es-419" => Self::Spanish,
+            _ => Self::English,
+        }
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        match locale {
+            Locale::English => "en-US",
+            Locale::German => "de",
+            Locale::Spanish => "es-ES",
+        }
+        .to_string()
+    }
+}
+
+/// Every user-facing string of the bot. Templates mark the spot for a
+/// user's name with `{name}`.
+pub struct Catalogue {
+    pub help_name: &'static str,
+    pub help_description: &'static str,
+    pub start_name: &'static str,
+    pub start_description: &'static str,
+    pub stop_name: &'static str,
+    pub stop_description: &'static str,
+    pub entry_name: &'static str,
+    pub entry_description: &'static str,
+    pub entry_option_name: &'static str,
+    pub entry_option_description: &'static str,
+    pub send_gratitude_name: &'static str,
+
+    pub help_title: &'static str,
+    pub help: &'static str,
+    pub welcome: &'static str,
+    pub goodbye: &'static str,
+    pub success: &'static str,
+    pub error: &'static str,
+    pub not_implemented: &'static str,
+    pub unsupported: &'static str,
+    pub dms_closed: &'static str,
+    pub already_active: &'static str,
+    pub not_active: &'static str,
+    pub paused: &'static str,
+    pub past_entry: &'static str,
+    pub no_past_entry: &'static str,
+    pub entry_added: &'static str,
+    pub entry_button: &'static str,
+    pub journal_title: &'static str,
+    pub entry_input_label: &'static str,
+    pub entry_input_placeholder: &'static str,
+
+    pub gratitude_title: &'static str,
+    pub gratitude_note_label: &'static str,
+    pub gratitude_note_placeholder: &'static str,
+    pub gratitude_anonymous_label: &'static str,
+    pub gratitude_anonymous_placeholder: &'static str,
+    pub gratitude_yes: &'static [&'static str],
+    pub gratitude_received: &'static str,
+    pub gratitude_someone: &'static str,
+    pub gratitude_given_entry: &'static str,
+    pub gratitude_received_entry: &'static str,
+    pub gratitude_sent: &'static str,
+    pub gratitude_self: &'static str,
+    pub gratitude_bot: &'static str,
+    pub gratitude_undeliverable: &'static str,
+}
@@ -0,0 +1,107 @@
+use super::Catalogue;
+
+pub const CATALOGUE: Catalogue = Catalogue {
+    help_name: "hilfe",
+    help_description: "Erfahre mehr über den Bot!",
+    start_name: "start",
+    start_description: "Erhalte ab jetzt Erinnerungen vom Bot!",
+    stop_name: "stopp",
+    stop_description: "Erhalte keine Erinnerungen mehr vom Bot!",
+    entry_name: "eintrag",
+    entry_description: "Füge deinem Dankbarkeitstagebuch einen Eintrag hinzu!",
+    entry_option_name: "eintrag",
+    entry_option_description: "Irgendetwas, wofür du gerade dankbar bist!",
+    send_gratitude_name: "Dankbarkeit senden",
+
+    help_title: "Willkommen bei Gratitude Bot!",
+    help: concat!(
+        "*Dieser Bot lenkt deinen Blick auf die schönen Dinge im Leben!*\n\n",
+        "Dafür erinnert er dich zufällig, im Schnitt alle paar Tage, einen ",
+        "Eintrag in das Dankbarkeitstagebuch zu schreiben, das er für dich führt, ",
+        "und zeigt dir dabei Dinge, für die du in der Vergangenheit dankbar warst. ",
+        "Alles ist erlaubt: Die kleinste Sache, die dich heute zum Lächeln gebracht ",
+        "hat, oder das große Ereignis, das letzten Monat dein Leben verändert hat. ",
+        "Mit der Zeit wird dein Gehirn die schönen Dinge im Leben bewusster ",
+        "wahrnehmen und dir helfen, zu schätzen, was du gerade hast!\n\n",
+        "Mit **/start** meldest du dich für die Erinnerungen an, mit **/stopp** ",
+        "beendest du sie, und mit **/eintrag** kannst du jederzeit etwas in dein ",
+        "Tagebuch schreiben!\n\n",
+        "Möchtest du dich bei jemandem bedanken? Klicke mit der rechten Maustaste ",
+        "auf die Person und wähle **Apps → Dankbarkeit senden**, um ihr eine ",
+        "Nachricht zu schicken!\n\n",
+        "*Der Bot ist Open Source, und du kannst dir den Code hier ansehen ",
+        "(und kopieren!): <https://github.com/Fittiboy/gratitude>!*\n\n",
+        "Die Inspiration für diesen Bot kam zu großen Teilen aus diesem Video ",
+        "von Kurzgesagt: https://youtu.be/WPPPFqsECz0",
+    ),
+    welcome: concat!(
+        "**Hallo! Danke, dass du Gratitude Bot verwendest! 🥳**\n",
+        "Der Bot erinnert dich ungefähr alle paar Tage daran, über etwas ",
+        "nachzudenken, wofür du dankbar bist, und es in dein Tagebuch zu ",
+        "schreiben! Mit /stopp kannst du diese Erinnerungen jederzeit beenden.",
+        "\n\n👇 Klicke auf den Knopf unten, um gleich jetzt einen Eintrag zu schreiben!"
+    ),
+    goodbye: concat!(
+        "**Du erhältst keine Erinnerungen mehr! Bis bald! 😊**\n",
+        "Denk daran, dass du mit **/eintrag** weiterhin Einträge schreiben ",
+        "und mit **/start** die Erinnerungen wieder aktivieren kannst!"
+    ),
+    success: "**Sieht so aus, als hätte das geklappt! 🥳** Falls nicht, melde dich bei Fitti#6969",
+    error: "Oh nein! Da ist wohl etwas schiefgelaufen!\nFrag Fitti#6969 um Hilfe!",
+    not_implemented: "Dieser Befehl ist noch nicht fertig! Bald verfügbar!",
+    unsupported: "Das kann der Bot leider noch nicht! Mit **/hilfe** siehst du, was er alles kann.",
+    dms_closed: concat!(
+        "Der Bot kann dir anscheinend keine Direktnachrichten schicken! ",
+        "Überprüfe deine Privatsphäre-Einstellungen: ",
+        "https://support.discord.com/hc/de/articles/217916488-Blocking-Privacy-Settings",
+    ),
+    already_active: concat!(
+        "Du bist bereits angemeldet! ",
+        "Der Bot schickt dir alle paar Tage zufällig eine Erinnerung.\n",
+        "Mit **/stopp** kannst du diese Erinnerungen beenden! ",
+        "Denk daran, dass du mit **/eintrag** jederzeit etwas in dein ",
+        "Tagebuch schreiben kannst!"
+    ),
+    not_active: concat!(
+        "Du bist nicht angemeldet! ",
+        "Der Bot schickt dir keine Erinnerungen.\n",
+        "Mit **/start** kannst du diese Erinnerungen aktivieren! ",
+        "Denk daran, dass du mit **/eintrag** jederzeit etwas in dein ",
+        "Tagebuch schreiben kannst!"
+    ),
+    paused: concat!(
+        "**Wir haben deine Erinnerungen pausiert! ⏸️**\n",
+        "Der Bot konnte dir eine Weile keine Direktnachrichten schicken, ",
+        "also hat er damit aufgehört. Mit **/start** erhältst du wieder Erinnerungen!"
+    ),
+    past_entry: "Hierfür warst du in der Vergangenheit dankbar:",
+    no_past_entry: "Ich hoffe, du hast einen schönen Tag!",
+    entry_added: "Du hast folgenden Eintrag hinzugefügt:",
+    entry_button: "Wofür bist du heute dankbar?",
+    journal_title: "Tagebuch von {name}",
+    entry_input_label: "Wofür bist du dankbar?",
+    entry_input_placeholder:
+        "Heute bin ich dankbar für… (ein leckeres Essen, ein Lächeln, meine perfekte Einparkkunst)",
+
+    gratitude_title: "Danke, {name}!",
+    gratitude_note_label: "Wofür möchtest du dich bedanken?",
+    gratitude_note_placeholder: "Danke für deine Hilfe gestern, das hat meinen Tag gerettet!",
+    gratitude_anonymous_label: "Anonym senden? (ja/nein)",
+    gratitude_anonymous_placeholder: "nein",
+    gratitude_yes: &["ja", "j", "yes", "y"],
+    gratitude_received: "{name} möchte sich bei dir bedanken! 💌",
+    gratitude_someone: "Jemand",
+    gratitude_given_entry: "💌 Du hast dich bei {name} bedankt:",
+    gratitude_received_entry: "💌 {name} hat sich bei dir bedankt:",
+    gratitude_sent:
+        "**Deine Nachricht ist unterwegs! 💌** Sie steht jetzt auch in deinem Tagebuch!",
+    gratitude_self: concat!(
+        "Du kannst dir nicht selbst danken! ",
+        "Nutze stattdessen **/eintrag**, um etwas in dein eigenes Tagebuch zu schreiben!"
+    ),
+    gratitude_bot: "Bots können keinen Dank empfangen, aber schön, dass du an sie denkst! 🤖",
+    gratitude_undeliverable: concat!(
+        "Der Bot kann dieser Person anscheinend keine Direktnachrichten schicken, ",
+        "deshalb konnte deine Nachricht nicht zugestellt werden!"
+    ),
+};
@@ -0,0 +1,101 @@
+use super::Catalogue;
+
+pub const CATALOGUE: Catalogue = Catalogue {
+    help_name: "help",
+    help_description: "Get some information about the bot!",
+    start_name: "start",
+    start_description: "Start receiving reminders from the bot!",
+    stop_name: "stop",
+    stop_description: "Stop receiving reminders from the bot!",
+    entry_name: "entry",
+    entry_description: "Add an entry to your gratitude journal!",
+    entry_option_name: "entry",
+    entry_option_description: "Something, anything, you are feeling grateful for!",
+    send_gratitude_name: "Send gratitude",
+
+    help_title: "Welcome to Gratitude Bot!",
+    help: concat!(
+        "*This bot makes you focus on the positive things in life!*\n\n",
+        "It does this by randomly, once every few days on average, nudging ",
+        "you to add an entry to the gratitude journal it keeps for you, while ",
+        "reminding you of things you said you were grateful for in the past. ",
+        "Anything goes here: The smallest thing that made you smile today, or ",
+        "that big event that changed your life last month. Over time, your ",
+        "brain will change to be more aware of the nice things in life, help ",
+        "you appreciate what you have right now!\n\nYou can use **/start** to ",
+        "sign up for those reminders, **/stop** to stop receiving them, and ",
+        "**/entry** to add something to the journal at any point!\n\n",
+        "Want to thank someone? Right-click them and choose ",
+        "**Apps → Send gratitude** to send them a note!\n\n",
+        "*The bot is open source, and you can view (and copy!) the code ",
+        "right here: <https://github.com/Fittiboy/gratitude>!*\n\n",
+        "The inspiration for this bot came in great part from this video ",
+        "by Kurzgesagt: https://youtu.be/WPPPFqsECz0",
+    ),
+    welcome: concat!(
+        "**Hi there! Thank you for deciding to use Gratitude Bot! 🥳**\n",
+        "The bot will send you reminders, every few days or so, to ",
+        "think about someting you are grateful for, and ask you to add it ",
+        "to your journal! You can use /stop at any time to stop these reminders",
+        "\n\n👇 Click the button below to make an entry into your journal right now!"
+    ),
+    goodbye: concat!(
+        "**You will no longer receive reminders! See you around! 😊**\n",
+        "Rememeber that you can still use **/entry** to make entries, ",
+        "and **/start** to receive these reminders again!"
+    ),
+    success: "**It looks like that worked! 🥳** If it didn't do what you expected, contact Fitti#6969",
+    error: "Oh no! It looks like something went wrong!\nAsk Fitti#6969 for help!",
+    not_implemented: "This command is not yet implemented! Coming soon!",
+    unsupported: "Sorry, the bot doesn't know how to handle that yet! Try **/help** to see what it can do.",
+    dms_closed: concat!(
+        "It looks like the bot can't DM you! Check your privacy settings: ",
+        "https://support.discord.com/hc/en-us/articles/217916488-Blocking-Privacy-Settings",
+    ),
+    already_active: concat!(
+        "Looks like you're already an active user! ",
+        "The bot will randomly send you reminders every few days.\n",
+        "Use the **/stop** command to stop receiving those reminders! ",
+        "Remember that you can use **/entry** to add something to your ",
+        "journal at any time!"
+    ),
+    not_active: concat!(
+        "Looks like you're not an active user! ",
+        "The bot will not send you reminders.\n",
+        "Use the **/start** command to start receiving those reminders! ",
+        "Remember that you can always use **/entry** to add something to ",
+        "your journal!"
+    ),
+    paused: concat!(
+        "**We paused your reminders! ⏸️**\n",
+        "The bot couldn't reach you in DMs for a while, so it stopped ",
+        "sending them. Use **/start** to receive reminders again!"
+    ),
+    past_entry: "Here's something you said you were grateful for in the past:",
+    no_past_entry: "Hope you're having a great day!",
+    entry_added: "You added the following entry:",
+    entry_button: "What are you grateful for today?",
+    journal_title: "{name}'s Gratitude Journal",
+    entry_input_label: "Express your gratitude for something!",
+    entry_input_placeholder:
+        "Today, I am grateful for… (a nice meal, someone smiling at me, how I perfectly parked my car)",
+
+    gratitude_title: "Thank {name}!",
+    gratitude_note_label: "What would you like to thank them for?",
+    gratitude_note_placeholder: "Thank you for helping me out yesterday, it really made my day!",
+    gratitude_anonymous_label: "Send anonymously? (yes/no)",
+    gratitude_anonymous_placeholder: "no",
+    gratitude_yes: &["yes", "y"],
+    gratitude_received: "{name} wanted to thank you! 💌",
+    gratitude_someone: "Someone",
+    gratitude_given_entry: "💌 You thanked {name}:",
+    gratitude_received_entry: "💌 {name} thanked you:",
+    gratitude_sent: "**Your note is on its way! 💌** It was added to your journal, too!",
+    gratitude_self: concat!(
+        "You can't send gratitude to yourself! ",
+        "Use **/entry** to add something to your own journal instead!"
+    ),
+    gratitude_bot: "Bots can't receive gratitude, but thanks for thinking of them! 🤖",
+    gratitude_undeliverable:
+        "It looks like the bot can't DM them, so your note couldn't be delivered!",
+};
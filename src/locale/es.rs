@@ -0,0 +1,106 @@
+use super::Catalogue;
+
+pub const CATALOGUE: Catalogue = Catalogue {
+    help_name: "ayuda",
+    help_description: "¡Obtén información sobre el bot!",
+    start_name: "iniciar",
+    start_description: "¡Empieza a recibir recordatorios del bot!",
+    stop_name: "detener",
+    stop_description: "¡Deja de recibir recordatorios del bot!",
+    entry_name: "entrada",
+    entry_description: "¡Añade una entrada a tu diario de gratitud!",
+    entry_option_name: "entrada",
+    entry_option_description: "¡Algo, cualquier cosa, por lo que te sientas agradecido!",
+    send_gratitude_name: "Enviar gratitud",
+
+    help_title: "¡Bienvenido a Gratitude Bot!",
+    help: concat!(
+        "*¡Este bot te ayuda a centrarte en las cosas positivas de la vida!*\n\n",
+        "Para ello, de forma aleatoria y en promedio una vez cada pocos días, te ",
+        "anima a añadir una entrada al diario de gratitud que guarda para ti, ",
+        "mientras te recuerda cosas por las que dijiste estar agradecido en el pasado. ",
+        "Aquí vale todo: Lo más pequeño que te hizo sonreír hoy, o ese gran ",
+        "acontecimiento que cambió tu vida el mes pasado. Con el tiempo, tu cerebro ",
+        "se volverá más consciente de las cosas bonitas de la vida y te ayudará a ",
+        "apreciar lo que tienes ahora mismo.\n\nPuedes usar **/iniciar** para ",
+        "apuntarte a esos recordatorios, **/detener** para dejar de recibirlos, y ",
+        "**/entrada** para añadir algo al diario en cualquier momento.\n\n",
+        "¿Quieres darle las gracias a alguien? Haz clic derecho sobre esa persona y ",
+        "elige **Aplicaciones → Enviar gratitud** para mandarle una nota.\n\n",
+        "*El bot es de código abierto, y puedes ver (¡y copiar!) el código ",
+        "aquí: <https://github.com/Fittiboy/gratitude>*\n\n",
+        "La inspiración para este bot vino en gran parte de este vídeo ",
+        "de Kurzgesagt: https://youtu.be/WPPPFqsECz0",
+    ),
+    welcome: concat!(
+        "**¡Hola! ¡Gracias por usar Gratitude Bot! 🥳**\n",
+        "El bot te enviará recordatorios, más o menos cada pocos días, para que ",
+        "pienses en algo por lo que estás agradecido y lo añadas a tu diario. ",
+        "Puedes usar /detener en cualquier momento para dejar de recibirlos.",
+        "\n\n👇 ¡Haz clic en el botón de abajo para escribir una entrada ahora mismo!"
+    ),
+    goodbye: concat!(
+        "**¡Ya no recibirás más recordatorios! ¡Hasta pronto! 😊**\n",
+        "Recuerda que todavía puedes usar **/entrada** para escribir entradas, ",
+        "y **/iniciar** para volver a recibir los recordatorios."
+    ),
+    success:
+        "**¡Parece que ha funcionado! 🥳** Si no hizo lo que esperabas, contacta con Fitti#6969",
+    error: "¡Oh no! Parece que algo ha salido mal.\n¡Pide ayuda a Fitti#6969!",
+    not_implemented: "¡Este comando todavía no está disponible! ¡Muy pronto!",
+    unsupported:
+        "¡Lo siento, el bot todavía no sabe hacer eso! Usa **/ayuda** para ver lo que puede hacer.",
+    dms_closed: concat!(
+        "¡Parece que el bot no puede enviarte mensajes directos! ",
+        "Revisa tu configuración de privacidad: ",
+        "https://support.discord.com/hc/es/articles/217916488-Blocking-Privacy-Settings",
+    ),
+    already_active: concat!(
+        "¡Parece que ya estás apuntado! ",
+        "El bot te enviará recordatorios al azar cada pocos días.\n",
+        "¡Usa el comando **/detener** para dejar de recibirlos! ",
+        "Recuerda que puedes usar **/entrada** para añadir algo a tu ",
+        "diario en cualquier momento."
+    ),
+    not_active: concat!(
+        "¡Parece que no estás apuntado! ",
+        "El bot no te enviará recordatorios.\n",
+        "¡Usa el comando **/iniciar** para empezar a recibirlos! ",
+        "Recuerda que siempre puedes usar **/entrada** para añadir algo a ",
+        "tu diario."
+    ),
+    paused: concat!(
+        "**¡Hemos pausado tus recordatorios! ⏸️**\n",
+        "El bot no pudo enviarte mensajes directos durante un tiempo, así que ",
+        "dejó de enviarlos. ¡Usa **/iniciar** para volver a recibirlos!"
+    ),
+    past_entry: "Esto es algo por lo que dijiste estar agradecido en el pasado:",
+    no_past_entry: "¡Espero que estés teniendo un gran día!",
+    entry_added: "Has añadido la siguiente entrada:",
+    entry_button: "¿Por qué estás agradecido hoy?",
+    journal_title: "Diario de {name}",
+    entry_input_label: "¿Por qué estás agradecido?",
+    entry_input_placeholder:
+        "Hoy estoy agradecido por… (una buena comida, la sonrisa de alguien, lo bien que aparqué)",
+
+    gratitude_title: "¡Gracias, {name}!",
+    gratitude_note_label: "¿Por qué quieres darle las gracias?",
+    gratitude_note_placeholder: "¡Gracias por ayudarme ayer, me alegraste el día!",
+    gratitude_anonymous_label: "¿Enviar de forma anónima? (sí/no)",
+    gratitude_anonymous_placeholder: "no",
+    gratitude_yes: &["sí", "si", "s", "yes", "y"],
+    gratitude_received: "¡{name} quiere darte las gracias! 💌",
+    gratitude_someone: "Alguien",
+    gratitude_given_entry: "💌 Le diste las gracias a {name}:",
+    gratitude_received_entry: "💌 {name} te dio las gracias:",
+    gratitude_sent: "**¡Tu nota está en camino! 💌** ¡También se ha añadido a tu diario!",
+    gratitude_self: concat!(
+        "¡No puedes darte las gracias a ti mismo! ",
+        "Usa **/entrada** para añadir algo a tu propio diario."
+    ),
+    gratitude_bot: "Los bots no pueden recibir gratitud, ¡pero gracias por pensar en ellos! 🤖",
+    gratitude_undeliverable: concat!(
+        "Parece que el bot no puede enviarle mensajes directos, ",
+        "así que tu nota no se pudo entregar."
+    ),
+};
@@ -0,0 +1,128 @@
+//! `GET /health` and `GET /status` for uptime checks, answering 503 when a
+//! KV binding or required variable is missing.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use worker::kv::KvStore;
+use worker::{console_error, Context, Date, Env, Request, Response, Result, RouteContext};
+
+use crate::users;
+
+/// Without these, the bot can't verify or answer interactions.
+const REQUIRED_VARS: [&str; 3] = [
+    "DISCORD_PUBLIC_KEY",
+    "DISCORD_APPLICATION_ID",
+    "DISCORD_TOKEN",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum Job {
+    Reminders,
+    CommandSync,
+}
+
+impl Job {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Reminders => "STATUS LAST REMINDERS",
+            Self::CommandSync => "STATUS LAST COMMAND SYNC",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Health {
+    healthy: bool,
+    kv: BTreeMap<&'static str, bool>,
+    vars: BTreeMap<&'static str, bool>,
+}
+
+/// Times are in milliseconds since the epoch, and `null` if unknown.
+#[derive(Debug, Serialize)]
+struct Status {
+    #[serde(flatten)]
+    health: Health,
+    last_reminders: Option<u64>,
+    last_command_sync: Option<u64>,
+    active_users: Option<usize>,
+}
+
+/// Remembers that `job` just finished successfully.
+pub async fn record(kv: &KvStore, job: Job) {
+    let now = Date::now().as_millis().to_string();
+    let recorded = match kv.put(job.key(), now) {
+        Ok(task) => task.execute().await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = recorded {
+        console_error!("Couldn't record {:?} run: {}", job, err);
+    }
+}
+
+async fn last(kv: &KvStore, job: Job) -> Option<u64> {
+    kv.get(job.key()).text().await.ok()??.parse().ok()
+}
+
+pub async fn health(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let health = check(&ctx.env).await;
+    respond(health.healthy, &health)
+}
+
+pub async fn status(_: Request, ctx: RouteContext<Context>) -> Result<Response> {
+    let health = check(&ctx.env).await;
+    let (last_reminders, last_command_sync) = match ctx.kv("thankful") {
+        Ok(kv) => (
+            last(&kv, Job::Reminders).await,
+            last(&kv, Job::CommandSync).await,
+        ),
+        Err(_) => (None, None),
+    };
+    let active_users = match ctx.kv("grateful_users") {
+        Ok(kv) => users::registered(&kv)
+            .await
+            .ok()
+            .map(|users| users.iter().filter(|user| user.paused.is_none()).count()),
+        Err(_) => None,
+    };
+    respond(
+        health.healthy,
+        &Status {
+            health,
+            last_reminders,
+            last_command_sync,
+            active_users,
+        },
+    )
+}
+
+async fn check(env: &Env) -> Health {
+    let mut kv = BTreeMap::new();
+    for binding in ["thankful", "grateful_users"] {
+        kv.insert(binding, reachable(env, binding).await);
+    }
+    let vars: BTreeMap<_, _> = REQUIRED_VARS
+        .into_iter()
+        .map(|name| {
+            let present = env.var(name).is_ok_and(|var| !var.to_string().is_empty());
+            (name, present)
+        })
+        .collect();
+    Health {
+        healthy: kv.values().chain(vars.values()).all(|ok| *ok),
+        kv,
+        vars,
+    }
+}
+
+/// Reads a key that never exists, which still takes a round trip to KV.
+async fn reachable(env: &Env, binding: &str) -> bool {
+    match env.kv(binding) {
+        Ok(kv) => kv.get("STATUS PROBE").text().await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn respond<T: Serialize>(healthy: bool, body: &T) -> Result<Response> {
+    let status = if healthy { 200 } else { 503 };
+    Ok(Response::from_json(body)?.with_status(status))
+}
@@ -1,54 +1,56 @@
-use crate::discord;
-use crate::interaction::SingleButtonMessage;
-use rand::seq::SliceRandom;
+use crate::discord::{self, DiscordError};
+use crate::error;
+use crate::interaction::{dm_channel, NoComponentMessage, SingleButtonMessage};
+use crate::journal;
+use crate::locale::Locale;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use serde_json::from_str;
 use worker::kv::KvStore;
-use worker::{console_error, console_log, kv};
+use worker::{console_error, console_log, kv, Env};
 
-pub async fn registered(kv: &KvStore) -> Vec<BotUser> {
+/// Undeliverable reminders in a row before a user is paused. Only failures
+/// caused by the recipient count.
+const MAX_FAILURES: u32 = 3;
+
+/// Reminders sent per run, keeping within the subrequest cap of one
+/// invocation.
+const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// The users waiting for a reminder, stored in the `thankful` KV.
+const QUEUE_KEY: &str = "PROMPT QUEUE";
+
+pub async fn registered(kv: &KvStore) -> Result<Vec<BotUser>, error::Handler> {
     kv.get("users")
         .json::<Vec<BotUser>>()
-        .await
-        .unwrap_or_else(|err| {
-            console_error!("Couldn't parse string into vector of users: {}!", err);
-            panic!()
-        })
-        .unwrap_or_else(|| {
-            console_error!("No registered users!");
-            panic!()
-        })
-        .into_iter()
-        .collect()
+        .await?
+        .ok_or(error::Handler::NoUsers)
 }
 
-pub async fn update(users: &mut Vec<BotUser>, kv: &kv::KvStore) {
+pub async fn update(users: &mut Vec<BotUser>, kv: &kv::KvStore) -> Result<(), error::Handler> {
     loop {
         let mut to_delete = Vec::new();
         let mut to_add = Vec::new();
 
-        let todo = kv.list().execute().await.unwrap();
+        let todo = kv.list().execute().await?;
 
         let mut keys = todo.keys;
         keys.retain(|key| key.name != "users");
         for key in keys.as_slice() {
-            match key.name {
-                ref name if name.starts_with("DELETE") => {
-                    let uid = name.as_str().split_once(' ').unwrap().1;
-                    to_delete.push(uid.to_owned());
-                }
-                ref name if name.starts_with("ADD") => {
-                    let user = name.as_str().split_once(' ').unwrap().1;
-                    to_add.push(user.to_owned());
-                }
-                ref name => {
-                    console_log!("Ignoring key: {}!", name);
-                }
+            match key.name.split_once(' ') {
+                Some(("DELETE", uid)) => to_delete.push(uid.to_owned()),
+                Some(("ADD", user)) => to_add.push(user.to_owned()),
+                _ => console_log!("Ignoring key: {}!", key.name),
             }
         }
         for user in to_add.as_slice() {
-            users.push(from_str::<BotUser>(user).unwrap());
+            match from_str::<BotUser>(user) {
+                Ok(user) => {
+                    users.retain(|old| old.uid != user.uid);
+                    users.push(user);
+                }
+                Err(err) => console_error!("Couldn't parse user {}: {}", user, err),
+            }
         }
         users.retain(|user| !to_delete.contains(&user.uid));
         for key in keys {
@@ -57,56 +59,200 @@ pub async fn update(users: &mut Vec<BotUser>, kv: &kv::KvStore) {
                 Err(err) => console_error!("Couldn't remove user {}: {}", &key.name, err),
             };
         }
-        match kv.put("users", &users) {
-            Ok(task) => match task.execute().await {
-                Ok(_) => console_log!("Updated users!"),
-                Err(err) => console_error!("Couldn't update users {}", err),
-            },
-            Err(err) => console_error!("Couldn't update users: {}", err),
-        }
+        save(users, kv).await;
         if todo.list_complete {
             break;
         }
     }
+    Ok(())
+}
+
+pub async fn save(users: &[BotUser], kv: &kv::KvStore) {
+    match kv.put("users", users) {
+        Ok(task) => match task.execute().await {
+            Ok(_) => console_log!("Updated users!"),
+            Err(err) => console_error!("Couldn't update users {}", err),
+        },
+        Err(err) => console_error!("Couldn't update users: {}", err),
+    }
+}
+
+/// Saves the delivery state of a single user into the current list, so
+/// registrations applied since the list was read aren't lost.
+pub async fn save_user(user: &BotUser, kv: &kv::KvStore) -> Result<(), error::Handler> {
+    let mut users = registered(kv).await?;
+    if let Some(current) = users.iter_mut().find(|current| current.uid == user.uid) {
+        current.failures = user.failures;
+        current.paused = user.paused.clone();
+        save(&users, kv).await;
+    }
+    Ok(())
+}
+
+/// `PROMPT_BATCH_SIZE` overrides how many reminders a run may send.
+pub fn batch_size(env: &Env) -> usize {
+    env.var("PROMPT_BATCH_SIZE")
+        .ok()
+        .and_then(|size| size.to_string().parse().ok())
+        .unwrap_or(DEFAULT_BATCH_SIZE)
 }
 
-pub async fn prompt(users: &[BotUser], kv: &KvStore, client: &mut discord::Client) {
+/// Draws a random selection of the active users, each with a chance of 1
+/// in 60. Drawn hourly, that is a reminder every few days.
+pub fn draw(users: &[BotUser]) -> Vec<String> {
     let mut rng = thread_rng();
-    let users = users.iter().filter(|_| rng.gen_range(1..=60) == 1);
+    users
+        .iter()
+        .filter(|user| user.paused.is_none())
+        .filter(|_| rng.gen_range(1..=60) == 1)
+        .map(|user| user.uid.clone())
+        .collect()
+}
+
+/// The users drawn for a reminder that haven't received it yet, in order.
+pub async fn queued(kv: &KvStore) -> Result<Vec<String>, error::Handler> {
+    Ok(kv
+        .get(QUEUE_KEY)
+        .json::<Vec<String>>()
+        .await?
+        .unwrap_or_default())
+}
+
+async fn save_queue(kv: &KvStore, queue: &[String]) -> Result<(), error::Handler> {
+    kv.put(QUEUE_KEY, queue)?.execute().await?;
+    Ok(())
+}
 
-    for user in users {
-        user.prompt(kv, client).await;
+/// Adds the drawn users to the end of the queue, unless they're waiting
+/// for a reminder already.
+pub async fn enqueue(kv: &KvStore, drawn: Vec<String>) -> Result<(), error::Handler> {
+    let mut queue = queued(kv).await?;
+    for uid in drawn {
+        if !queue.contains(&uid) {
+            queue.push(uid);
+        }
+    }
+    console_log!("{} users waiting for a reminder", queue.len());
+    save_queue(kv, &queue).await
+}
+
+/// Reminds the next `batch_size` users in the queue. Delivery failures
+/// change the users, so the caller should `save` them afterwards.
+pub async fn prompt(
+    users: &mut [BotUser],
+    kv: &KvStore,
+    client: &mut discord::Client,
+    batch_size: usize,
+) -> Result<(), error::Handler> {
+    let mut queue = queued(kv).await?;
+    let batch: Vec<String> = queue.drain(..batch_size.min(queue.len())).collect();
+    // Saved before sending, so a run that dies halfway can't remind the
+    // same users again.
+    save_queue(kv, &queue).await?;
+
+    for uid in batch {
+        match users.iter_mut().find(|user| user.uid == uid) {
+            Some(user) if user.paused.is_none() => {
+                let _ = user.prompt(kv, client).await;
+            }
+            _ => console_log!("Skipping {}, who is no longer active", uid),
+        }
     }
+    Ok(())
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct BotUser {
     pub uid: String,
     pub channel_id: String,
+    #[serde(default)]
+    pub locale: Locale,
+    /// Reminders in a row that couldn't be delivered.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failures: u32,
+    /// Why reminders to this user were paused, if they were.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<String>,
+}
+
+fn is_zero(failures: &u32) -> bool {
+    *failures == 0
+}
+
+fn paused_key(uid: &str) -> String {
+    format!("PAUSED {}", uid)
+}
+
+/// Tells a user that their reminders were paused, the first time they
+/// interact with the bot afterwards and it can reach them again.
+pub async fn notify_paused(kv: &KvStore, uid: &str, locale: Locale, client: &mut discord::Client) {
+    match kv.get(&paused_key(uid)).text().await {
+        Ok(Some(_)) => {}
+        Ok(None) => return,
+        Err(err) => {
+            console_error!("Couldn't look up paused user {}: {}", uid, err);
+            return;
+        }
+    }
+    let Some(channel_id) = dm_channel(uid, client).await else {
+        return;
+    };
+    let payload = NoComponentMessage::paused(locale);
+    if let Err(err) = client.create_message(&channel_id, &payload).await {
+        console_error!("Couldn't tell user {} they were paused: {}", uid, err);
+        return;
+    }
+    if let Err(err) = clear_paused(kv, uid).await {
+        console_error!("Couldn't clear paused user {}: {}", uid, err);
+    }
+}
+
+pub async fn clear_paused(kv: &KvStore, uid: &str) -> Result<(), kv::KvError> {
+    kv.delete(&paused_key(uid)).await
 }
 
 impl BotUser {
-    pub async fn prompt(&self, kv: &KvStore, client: &mut discord::Client) {
-        let entry = self.random_entry(kv).await;
-        let payload = SingleButtonMessage::from_entry(entry);
+    /// Sends the user a reminder. Failures are logged and counted here
+    /// already, and only returned for callers that report them.
+    pub async fn prompt(
+        &mut self,
+        kv: &KvStore,
+        client: &mut discord::Client,
+    ) -> Result<(), DiscordError> {
+        let entry = journal::random(kv, &self.uid).await.unwrap_or_else(|err| {
+            console_error!("Couldn't get entries of user {}: {}", self.uid, err);
+            None
+        });
+        let payload = SingleButtonMessage::from_entry(entry, self.locale);
 
-        let client = client
-            .post(&format!("channels/{}/messages", self.channel_id))
-            .json(&payload);
         console_log!("Prompting {}", self.uid);
-        if let Err(error) = client.send().await.unwrap().error_for_status() {
-            console_error!("Error sending message to user {}: {}", self.uid, error);
+        let sent = client.create_message(&self.channel_id, &payload).await;
+        match &sent {
+            Ok(()) => self.failures = 0,
+            Err(error) => {
+                console_error!("Error sending message to user {}: {}", self.uid, error);
+                if error.recipient_unreachable() {
+                    self.failures += 1;
+                    if self.failures >= MAX_FAILURES {
+                        self.pause(kv, error.to_string()).await;
+                    }
+                }
+            }
         }
+        sent
     }
 
-    async fn random_entry(&self, kv: &KvStore) -> Option<String> {
-        let entries = kv.get(&self.uid).text().await.unwrap();
-        console_log!("Entries: {:?}", entries);
-        let entries: Vec<String> = match entries {
-            Some(entries) => from_str(&entries).unwrap(),
-            None => Vec::new(),
-        };
-        let mut rng = rand::thread_rng();
-        entries.choose(&mut rng).cloned()
+    /// Stops reminding the user, until they use `/start` again.
+    async fn pause(&mut self, kv: &KvStore, reason: String) {
+        console_log!("Pausing {} after {} failures", self.uid, self.failures);
+        match kv.put(&paused_key(&self.uid), &reason) {
+            Ok(task) => {
+                if let Err(err) = task.execute().await {
+                    console_error!("Couldn't record paused user {}: {}", self.uid, err);
+                }
+            }
+            Err(err) => console_error!("Couldn't record paused user {}: {}", self.uid, err),
+        }
+        self.paused = Some(reason);
     }
 }
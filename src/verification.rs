@@ -1,5 +1,6 @@
 use ed25519_dalek::{PublicKey, Signature, SignatureError, Verifier};
 use hex::FromHexError;
+use std::cell::RefCell;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -11,22 +12,169 @@ pub enum Error {
 
     #[error("Invalid signature provided.")]
     InvalidSignature(ed25519_dalek::ed25519::Error),
+
+    #[error("No public key provided.")]
+    NoPublicKey,
+
+    #[error("Timestamp '{0}' is outside the accepted window.")]
+    TimestampOutOfWindow(String),
+}
+
+/// A validly signed request is only accepted within `max_skew` seconds of its
+/// timestamp, so a captured request can't be replayed later on.
+pub fn verify_timestamp(timestamp: &str, now: u64, max_skew: u64) -> Result<(), Error> {
+    match timestamp.parse::<u64>() {
+        Ok(sent) if sent.abs_diff(now) <= max_skew => Ok(()),
+        _ => Err(Error::TimestampOutOfWindow(timestamp.to_string())),
+    }
+}
+
+thread_local! {
+    /// Keys stay parsed for the lifetime of the isolate, along with the
+    /// variable they were parsed from, in case that changes.
+    static PUBLIC_KEYS: RefCell<Option<(String, Vec<PublicKey>)>> = const { RefCell::new(None) };
 }
 
+/// Parses a comma-separated list of hex-encoded public keys, reusing the
+/// result from earlier requests when possible.
+pub fn public_keys(keys: &str) -> Result<Vec<PublicKey>, Error> {
+    PUBLIC_KEYS.with(|cache| {
+        if let Some((raw, parsed)) = cache.borrow().as_ref() {
+            if raw == keys {
+                return Ok(parsed.clone());
+            }
+        }
+        let parsed = keys
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                hex::decode(key)
+                    .map_err(Error::ParseHexFailed)
+                    .and_then(|bytes| Ok(PublicKey::from_bytes(&bytes)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if parsed.is_empty() {
+            return Err(Error::NoPublicKey);
+        }
+        *cache.borrow_mut() = Some((keys.to_string(), parsed.clone()));
+        Ok(parsed)
+    })
+}
+
+/// Accepts the signature if any of the keys produced it, so that several
+/// applications, or an old and a new key, can share one deployment.
 pub fn verify_signature(
-    public_key: &str,
+    public_keys: &[PublicKey],
     signature: &str,
     timestamp: &str,
     body: &str,
 ) -> Result<(), Error> {
-    let public_key = &hex::decode(public_key)
+    let signature = hex::decode(signature)
         .map_err(Error::ParseHexFailed)
-        .and_then(|bytes| PublicKey::from_bytes(&bytes).map_err(Error::InvalidSignature))?;
-
-    Ok(public_key.verify(
-        format!("{}{}", timestamp, body).as_bytes(),
-        &hex::decode(signature)
-            .map_err(Error::ParseHexFailed)
-            .and_then(|bytes| Signature::from_bytes(&bytes).map_err(Error::InvalidSignature))?,
-    )?)
+        .and_then(|bytes| Signature::from_bytes(&bytes).map_err(Error::InvalidSignature))?;
+    let message = format!("{}{}", timestamp, body);
+
+    let mut result = Err(Error::NoPublicKey);
+    for public_key in public_keys {
+        result = public_key
+            .verify(message.as_bytes(), &signature)
+            .map_err(Error::InvalidSignature);
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn hex_key(seed: u8) -> String {
+        hex::encode(keypair(seed).public.as_bytes())
+    }
+
+    fn sign(seed: u8, timestamp: &str, body: &str) -> String {
+        let message = format!("{}{}", timestamp, body);
+        hex::encode(keypair(seed).sign(message.as_bytes()).to_bytes())
+    }
+
+    #[test]
+    fn timestamp_inside_window() {
+        assert!(verify_timestamp("1000", 1000, 300).is_ok());
+        assert!(verify_timestamp("1000", 1300, 300).is_ok());
+    }
+
+    #[test]
+    fn timestamp_outside_window() {
+        assert!(matches!(
+            verify_timestamp("1000", 1301, 300),
+            Err(Error::TimestampOutOfWindow(_))
+        ));
+    }
+
+    #[test]
+    fn timestamp_in_future() {
+        assert!(verify_timestamp("1300", 1000, 300).is_ok());
+        assert!(verify_timestamp("1301", 1000, 300).is_err());
+    }
+
+    #[test]
+    fn timestamp_not_numeric() {
+        for timestamp in ["", "soon", "-1000", "1000.5"] {
+            assert!(verify_timestamp(timestamp, 1000, 300).is_err());
+        }
+    }
+
+    #[test]
+    fn second_key_matches() {
+        let keys = public_keys(&format!("{}, {}", hex_key(1), hex_key(2))).unwrap();
+        assert_eq!(keys.len(), 2);
+        let signature = sign(2, "1000", "{}");
+        assert!(verify_signature(&keys, &signature, "1000", "{}").is_ok());
+        assert!(verify_signature(&keys, &signature, "1000", "{ }").is_err());
+
+        let signature = sign(3, "1000", "{}");
+        assert!(matches!(
+            verify_signature(&keys, &signature, "1000", "{}"),
+            Err(Error::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn no_keys() {
+        for keys in ["", " ", " , "] {
+            assert!(matches!(public_keys(keys), Err(Error::NoPublicKey)));
+        }
+        let signature = sign(1, "1000", "{}");
+        assert!(matches!(
+            verify_signature(&[], &signature, "1000", "{}"),
+            Err(Error::NoPublicKey)
+        ));
+    }
+
+    #[test]
+    fn bad_hex() {
+        assert!(matches!(
+            public_keys(&format!("{},not hex", hex_key(1))),
+            Err(Error::ParseHexFailed(_))
+        ));
+        assert!(matches!(
+            public_keys("abcd"),
+            Err(Error::InvalidPublicKey(_))
+        ));
+        let keys = public_keys(&hex_key(1)).unwrap();
+        assert!(matches!(
+            verify_signature(&keys, "not hex", "1000", "{}"),
+            Err(Error::ParseHexFailed(_))
+        ));
+    }
 }